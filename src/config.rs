@@ -2,6 +2,12 @@
 
 use std::env;
 
+use crate::escalate::EscalateConfig;
+use crate::hooks::HookConfig;
+use crate::notify::NotifyConfig;
+use crate::sort::Sort;
+use crate::InboxItem;
+
 /// Tael configuration
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -10,13 +16,31 @@ pub struct Config {
     /// - Zellij: "zellij action launch-plugin file:~/.config/zellij/plugins/tael-focus.wasm --floating --configuration pane_id={pane_id}"
     /// - tmux: "tmux select-pane -t {pane_id}"
     pub focus_command: Option<String>,
+    /// Command to switch to another multiplexer session. Use {session} as placeholder.
+    /// Examples:
+    /// - tmux: "tmux switch-client -t {session}"
+    /// - Zellij: none is detected (`zellij attach` nests a client), so set one
+    pub session_command: Option<String>,
+    /// Desktop notification settings (from TAEL_NOTIFY* env vars)
+    pub notify: NotifyConfig,
+    /// Commands run on inbox changes (from TAEL_ON_* env vars)
+    pub hooks: HookConfig,
+    /// Escalation of overdue urgent items (from TAEL_ESCALATE* env vars)
+    pub escalate: EscalateConfig,
+    /// Display order for list and the TUI (--sort / TAEL_SORT)
+    pub sort: Sort,
 }
 
 impl Config {
-    /// Create config with optional override, falling back to auto-detection
-    pub fn new(override_cmd: Option<String>) -> Self {
+    /// Create config with optional overrides, falling back to auto-detection
+    pub fn new(override_cmd: Option<String>, session_cmd: Option<String>) -> Self {
         Self {
             focus_command: override_cmd.or_else(Self::detect_focus_command),
+            session_command: session_cmd.or_else(Self::detect_session_command),
+            notify: NotifyConfig::from_env(),
+            hooks: HookConfig::from_env(),
+            escalate: EscalateConfig::from_env(),
+            sort: Sort::default(),
         }
     }
 
@@ -41,6 +65,51 @@ impl Config {
         }
     }
 
+    /// Auto-detect session switch command based on environment (tmux only:
+    /// `zellij attach` from inside Zellij nests a client instead of switching)
+    fn detect_session_command() -> Option<String> {
+        if env::var("TMUX").is_ok() {
+            Some("tmux switch-client -t {session}".to_string())
+        } else {
+            None
+        }
+    }
+
+    /// Detect the currently focused pane, if the multiplexer can tell us
+    pub fn focused_pane(&self) -> Option<u32> {
+        let output = |program: &str, args: &[&str]| {
+            std::process::Command::new(program)
+                .args(args)
+                .stderr(std::process::Stdio::null())
+                .output()
+                .ok()
+                .filter(|o| o.status.success())
+                .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+        };
+
+        if env::var("ZELLIJ").is_ok() {
+            // Rows look like: "1 terminal_3 claude"
+            let clients = output("zellij", &["action", "list-clients"])?;
+            clients.lines().skip(1).find_map(|line| {
+                line.split_whitespace()
+                    .nth(1)?
+                    .strip_prefix("terminal_")?
+                    .parse()
+                    .ok()
+            })
+        } else if env::var("TMUX").is_ok() {
+            // display-message would report the caller's own pane ($TMUX_PANE),
+            // so ask which pane the attached clients are looking at instead
+            let clients = output(
+                "tmux",
+                &["list-clients", "-F", "#{client_activity} #{pane_id}"],
+            )?;
+            active_tmux_pane(&clients)
+        } else {
+            None
+        }
+    }
+
     /// Execute focus command for a pane
     pub fn focus_pane(&self, pane_id: u32) -> Result<(), String> {
         let cmd = self
@@ -48,24 +117,74 @@ impl Config {
             .as_ref()
             .ok_or("No focus command configured")?;
 
-        let cmd = cmd.replace("{pane_id}", &pane_id.to_string());
+        run_command(&cmd.replace("{pane_id}", &pane_id.to_string()), "Focus")
+    }
 
-        // Parse command with shell-style quoting (handles spaces in arguments)
-        let parts = shell_words::split(&cmd)
-            .map_err(|e| format!("Failed to parse focus command: {}", e))?;
-        if parts.is_empty() {
-            return Err("Empty focus command".to_string());
+    /// Focus an item's pane, switching sessions first if it belongs to
+    /// another session (items tagged with a "session" attr)
+    pub fn focus_item(&self, item: &InboxItem) -> Result<(), String> {
+        let pane_id = item.pane_id().ok_or("Item has no pane")?;
+        if let Some(session) = item.get("session") {
+            if session != crate::file::current_session() {
+                let cmd = self
+                    .session_command
+                    .as_ref()
+                    .ok_or("No session switch command configured (set TAEL_SESSION_CMD)")?;
+                let target = crate::file::session_target(session);
+                run_command(&cmd.replace("{session}", target), "Session")?;
+            }
         }
+        self.focus_pane(pane_id)
+    }
+}
 
-        let status = std::process::Command::new(&parts[0])
-            .args(&parts[1..])
-            .status()
-            .map_err(|e| format!("Failed to execute focus command: {}", e))?;
+/// Pane shown by the most recently active client, from
+/// `tmux list-clients -F '#{client_activity} #{pane_id}'` output
+fn active_tmux_pane(clients: &str) -> Option<u32> {
+    clients
+        .lines()
+        .filter_map(|line| {
+            let (activity, pane) = line.trim().split_once(' ')?;
+            let pane = pane.trim_start_matches('%').parse().ok()?;
+            Some((activity.parse::<u64>().ok()?, pane))
+        })
+        .max_by_key(|(activity, _)| *activity)
+        .map(|(_, pane)| pane)
+}
 
-        if status.success() {
-            Ok(())
-        } else {
-            Err(format!("Focus command exited with: {}", status))
-        }
+/// Run a command line, reporting failures with a label ("Focus", "Session")
+fn run_command(cmd: &str, label: &str) -> Result<(), String> {
+    let kind = label.to_lowercase();
+    // Parse command with shell-style quoting (handles spaces in arguments)
+    let parts =
+        shell_words::split(cmd).map_err(|e| format!("Failed to parse {} command: {}", kind, e))?;
+    if parts.is_empty() {
+        return Err(format!("Empty {} command", kind));
+    }
+
+    let status = std::process::Command::new(&parts[0])
+        .args(&parts[1..])
+        .status()
+        .map_err(|e| format!("Failed to execute {} command: {}", kind, e))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} command exited with: {}", label, status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tmux_focus_follows_most_active_client() {
+        assert_eq!(
+            active_tmux_pane("1700000100 %3\n1700000200 %12\n"),
+            Some(12)
+        );
+        assert_eq!(active_tmux_pane("1700000100 %3\n"), Some(3));
+        assert_eq!(active_tmux_pane(""), None);
     }
 }
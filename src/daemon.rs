@@ -0,0 +1,646 @@
+//! Optional background daemon holding the inbox in memory
+//!
+//! Protocol: newline-delimited JSON over a Unix socket next to the inbox
+//! file. Each request carries the protocol version `v` and an `op`:
+//!
+//! ```text
+//! {"v":1,"op":"add","item":{"attrs":{"msg":"hi","pane":"42"},"status":"waiting"}}
+//! {"v":1,"op":"put","items":[{"attrs":{"id":"k3f9","msg":"hi"},"status":"working"}]}
+//! {"v":1,"op":"remove","pane":42}
+//! {"v":1,"op":"remove_id","id":"k3f9"}
+//! {"v":1,"op":"clear"}
+//! {"v":1,"op":"seen","id":"k3f9","msg":"hi"}
+//! {"v":1,"op":"list"}
+//! {"v":1,"op":"subscribe"}
+//! ```
+//!
+//! Every request gets one response line. `subscribe` keeps the connection
+//! open and sends a further response with the full inbox after each change;
+//! subscribers that stop reading are dropped.
+
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::escalate::{self, EscalateConfig};
+use crate::notify::NotifyConfig;
+use crate::{file, store, time, Inbox, InboxItem};
+
+/// Current protocol version
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// How long a write to a client may block before it is given up on
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often `tael daemon` checks for overdue urgent items
+pub const ESCALATE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Socket path for an inbox file (`foo.md` -> `foo.sock`)
+pub fn socket_path(inbox_path: &Path) -> PathBuf {
+    inbox_path.with_extension("sock")
+}
+
+/// Request operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum Op {
+    /// Add or update an item
+    Add { item: InboxItem },
+    /// Store items exactly as given, replacing those with the same id
+    Put { items: Vec<InboxItem> },
+    /// Remove an item by pane ID
+    Remove { pane: u32 },
+    /// Remove an item by id
+    #[serde(rename = "remove_id")]
+    RemoveId { id: String },
+    /// Remove all items
+    Clear,
+    /// Mark an item seen if it still shows this message (no history event)
+    Seen { id: String, msg: String },
+    /// Get the current inbox
+    List,
+    /// Get the current inbox, then again after every change
+    Subscribe,
+}
+
+/// Request envelope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Request {
+    pub v: u32,
+    #[serde(flatten)]
+    pub op: Op,
+}
+
+/// Response envelope
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Response {
+    pub v: u32,
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Full inbox (list, subscribe)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inbox: Option<Inbox>,
+    /// Replaced (add), removed (remove, clear) or marked (seen) items
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<InboxItem>,
+}
+
+impl Response {
+    fn ok() -> Self {
+        Self {
+            v: PROTOCOL_VERSION,
+            ok: true,
+            ..Default::default()
+        }
+    }
+
+    fn error(msg: impl Into<String>) -> Self {
+        Self {
+            v: PROTOCOL_VERSION,
+            ok: false,
+            error: Some(msg.into()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Shared daemon state
+struct State {
+    inbox: Inbox,
+    path: PathBuf,
+    /// Modification time of the file as we last saw it
+    mtime: Option<SystemTime>,
+    /// Update queues of subscribed clients, each drained by its own thread
+    subscribers: Vec<mpsc::Sender<Arc<str>>>,
+}
+
+impl State {
+    /// Reload from disk if something else (e.g. the TUI) changed the file
+    fn refresh(&mut self) -> io::Result<()> {
+        let mtime = file_mtime(&self.path);
+        if mtime != self.mtime {
+            self.inbox = file::load(&self.path)?;
+            self.mtime = mtime;
+        }
+        Ok(())
+    }
+
+    /// Record the file state after our own save and queue the new inbox for
+    /// subscribers (without blocking on them)
+    fn changed(&mut self) {
+        self.mtime = file_mtime(&self.path);
+        let line: Arc<str> = encode(&Response {
+            inbox: Some(self.inbox.clone()),
+            ..Response::ok()
+        })
+        .into();
+        self.subscribers
+            .retain(|s| s.send(Arc::clone(&line)).is_ok());
+    }
+}
+
+/// Forward queued updates to a subscriber until a write fails or times out
+fn forward(mut stream: UnixStream, updates: mpsc::Receiver<Arc<str>>) {
+    for line in updates {
+        if stream.write_all(line.as_bytes()).is_err() {
+            break;
+        }
+    }
+}
+
+fn file_mtime(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn encode<T: Serialize>(value: &T) -> String {
+    let mut line = serde_json::to_string(value).expect("protocol types serialize");
+    line.push('\n');
+    line
+}
+
+/// Daemon bound to an inbox's socket
+pub struct Server {
+    listener: UnixListener,
+    socket: PathBuf,
+    state: Arc<Mutex<State>>,
+}
+
+impl Server {
+    /// Load the inbox and bind its socket (replacing a stale socket file)
+    pub fn bind(inbox_path: &Path) -> io::Result<Self> {
+        let socket = socket_path(inbox_path);
+        if UnixStream::connect(&socket).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("daemon already running on {}", socket.display()),
+            ));
+        }
+        match fs::remove_file(&socket) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        if let Some(parent) = socket.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let listener = UnixListener::bind(&socket)?;
+
+        let state = State {
+            inbox: file::load(inbox_path)?,
+            path: inbox_path.to_path_buf(),
+            mtime: file_mtime(inbox_path),
+            subscribers: Vec::new(),
+        };
+        Ok(Self {
+            listener,
+            socket,
+            state: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    /// Socket this server listens on
+    pub fn socket(&self) -> &Path {
+        &self.socket
+    }
+
+    /// Remove the socket file when the process is interrupted or terminated
+    pub fn cleanup_on_signal(&self) -> io::Result<()> {
+        use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+
+        let mut signals = signal_hook::iterator::Signals::new([SIGHUP, SIGINT, SIGTERM])?;
+        let socket = self.socket.clone();
+        std::thread::spawn(move || {
+            if let Some(signal) = signals.forever().next() {
+                let _ = fs::remove_file(&socket);
+                std::process::exit(128 + signal);
+            }
+        });
+        Ok(())
+    }
+
+    /// Check for overdue urgent items every `interval` in the background, so
+    /// escalation notifications go out without a status bar or TUI polling
+    pub fn escalate_every(
+        &self,
+        interval: Duration,
+        config: EscalateConfig,
+        notify_config: NotifyConfig,
+    ) {
+        let state = Arc::clone(&self.state);
+        std::thread::spawn(move || loop {
+            std::thread::sleep(interval);
+            let (path, inbox) = {
+                let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+                if state.refresh().is_err() {
+                    continue;
+                }
+                (state.path.clone(), state.inbox.clone())
+            };
+            if let Err(e) = escalate::check(&config, &notify_config, &path, &inbox, time::now()) {
+                eprintln!("warning: {}", e);
+            }
+        });
+    }
+
+    /// Accept connections forever, one thread per client
+    pub fn run(self) -> io::Result<()> {
+        for stream in self.listener.incoming() {
+            let stream = stream?;
+            let state = Arc::clone(&self.state);
+            std::thread::spawn(move || {
+                let _ = handle_client(stream, &state);
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.socket);
+    }
+}
+
+fn handle_client(stream: UnixStream, state: &Mutex<State>) -> io::Result<()> {
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let reader = BufReader::new(stream);
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request: Request = match serde_json::from_str(&line) {
+            Ok(r) => r,
+            Err(e) => {
+                writer.write_all(
+                    encode(&Response::error(format!("bad request: {}", e))).as_bytes(),
+                )?;
+                continue;
+            }
+        };
+        if request.v != PROTOCOL_VERSION {
+            let msg = format!(
+                "unsupported protocol version {} (daemon speaks {})",
+                request.v, PROTOCOL_VERSION
+            );
+            writer.write_all(encode(&Response::error(msg)).as_bytes())?;
+            continue;
+        }
+
+        let subscribe = matches!(request.op, Op::Subscribe);
+        let line = {
+            let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+            let response = match handle_op(request.op, &mut state) {
+                Ok(r) => r,
+                Err(e) => Response::error(e.to_string()),
+            };
+            let line = encode(&response);
+            if subscribe && response.ok {
+                // Queue the snapshot first so it can't overtake later updates
+                let (updates, queue) = mpsc::channel();
+                updates.send(line.into()).expect("receiver not yet dropped");
+                state.subscribers.push(updates);
+                let stream = writer.try_clone()?;
+                std::thread::spawn(move || forward(stream, queue));
+                continue;
+            }
+            line
+        };
+        writer.write_all(line.as_bytes())?;
+    }
+    Ok(())
+}
+
+fn handle_op(op: Op, state: &mut State) -> io::Result<Response> {
+    state.refresh()?;
+    let path = state.path.clone();
+    let response = match op {
+        Op::Add { item } => {
+            let replaced = store::add_to(&mut state.inbox, &path, item)?;
+            state.changed();
+            Response {
+                items: replaced.into_iter().collect(),
+                ..Response::ok()
+            }
+        }
+        Op::Put { items } => {
+            store::put_many_to(&mut state.inbox, &path, items)?;
+            state.changed();
+            Response::ok()
+        }
+        Op::Remove { pane } => {
+            let removed = store::remove_from(&mut state.inbox, &path, pane)?;
+            if !removed.is_empty() {
+                state.changed();
+            }
+            Response {
+                items: removed,
+                ..Response::ok()
+            }
+        }
+        Op::RemoveId { id } => {
+            let removed = store::remove_id_from(&mut state.inbox, &path, &id)?;
+            if removed.is_some() {
+                state.changed();
+            }
+            Response {
+                items: removed.into_iter().collect(),
+                ..Response::ok()
+            }
+        }
+        Op::Clear => {
+            let removed = store::clear(&mut state.inbox, &path)?;
+            state.changed();
+            Response {
+                items: removed,
+                ..Response::ok()
+            }
+        }
+        Op::Seen { id, msg } => {
+            let seen = store::mark_seen_in(&mut state.inbox, &path, &id, &msg)?;
+            if seen.is_some() {
+                state.changed();
+            }
+            Response {
+                items: seen.into_iter().collect(),
+                ..Response::ok()
+            }
+        }
+        Op::List | Op::Subscribe => Response {
+            inbox: Some(state.inbox.clone()),
+            ..Response::ok()
+        },
+    };
+    Ok(response)
+}
+
+/// Connection to a running daemon
+pub struct Client {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+    inbox_path: PathBuf,
+}
+
+impl Client {
+    /// Connect to the daemon for an inbox file (None if none is running)
+    pub fn connect(inbox_path: &Path) -> Option<Self> {
+        let stream = UnixStream::connect(socket_path(inbox_path)).ok()?;
+        stream.set_read_timeout(Some(Duration::from_secs(5))).ok()?;
+        let writer = stream.try_clone().ok()?;
+        Some(Self {
+            reader: BufReader::new(stream),
+            writer,
+            inbox_path: inbox_path.to_path_buf(),
+        })
+    }
+
+    /// Inbox file the daemon serves
+    pub fn inbox_path(&self) -> &Path {
+        &self.inbox_path
+    }
+
+    /// Send one request and wait for its response. Errors reported by the
+    /// daemon have kind `Other`; any other kind means the connection failed.
+    pub fn request(&mut self, op: Op) -> io::Result<Response> {
+        let request = Request {
+            v: PROTOCOL_VERSION,
+            op,
+        };
+        self.writer.write_all(encode(&request).as_bytes())?;
+        let response = self.read_response()?;
+        if response.ok {
+            Ok(response)
+        } else {
+            Err(io::Error::other(
+                response.error.unwrap_or_else(|| "daemon error".to_string()),
+            ))
+        }
+    }
+
+    fn read_response(&mut self) -> io::Result<Response> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "daemon closed connection",
+            ));
+        }
+        serde_json::from_str(&line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Add or update an item, returning the replaced item if any
+    pub fn add(&mut self, item: InboxItem) -> io::Result<Option<InboxItem>> {
+        Ok(self.request(Op::Add { item })?.items.pop())
+    }
+
+    /// Store items exactly as given
+    pub fn put_many(&mut self, items: Vec<InboxItem>) -> io::Result<()> {
+        self.request(Op::Put { items }).map(|_| ())
+    }
+
+    /// Remove all items for a pane ID, returning them
+    pub fn remove(&mut self, pane: u32) -> io::Result<Vec<InboxItem>> {
+        Ok(self.request(Op::Remove { pane })?.items)
+    }
+
+    /// Remove an item by id, returning it if found
+    pub fn remove_id(&mut self, id: &str) -> io::Result<Option<InboxItem>> {
+        Ok(self
+            .request(Op::RemoveId { id: id.to_string() })?
+            .items
+            .pop())
+    }
+
+    /// Remove all unpinned items, returning them
+    pub fn clear(&mut self) -> io::Result<Vec<InboxItem>> {
+        Ok(self.request(Op::Clear)?.items)
+    }
+
+    /// Mark an item seen if it still shows `msg`, returning it if so
+    pub fn mark_seen(&mut self, id: &str, msg: &str) -> io::Result<Option<InboxItem>> {
+        Ok(self
+            .request(Op::Seen {
+                id: id.to_string(),
+                msg: msg.to_string(),
+            })?
+            .items
+            .pop())
+    }
+
+    /// Get the current inbox
+    pub fn list(&mut self) -> io::Result<Inbox> {
+        Ok(self.request(Op::List)?.inbox.unwrap_or_default())
+    }
+
+    /// Subscribe to changes: yields the current inbox, then one per change
+    pub fn subscribe(mut self) -> io::Result<impl Iterator<Item = io::Result<Inbox>>> {
+        self.writer.set_read_timeout(None)?;
+        let first = self.request(Op::Subscribe)?.inbox.unwrap_or_default();
+        let rest = std::iter::from_fn(move || {
+            Some(self.read_response().map(|r| r.inbox.unwrap_or_default()))
+        });
+        Ok(std::iter::once(Ok(first)).chain(rest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Store;
+    use crate::{test_utils::make_item, Status};
+    use tempfile::TempDir;
+
+    fn start(dir: &TempDir) -> PathBuf {
+        let path = dir.path().join("test.md");
+        let server = Server::bind(&path).unwrap();
+        std::thread::spawn(move || server.run());
+        path
+    }
+
+    #[test]
+    fn no_daemon_means_no_client() {
+        let dir = TempDir::new().unwrap();
+        assert!(Client::connect(&dir.path().join("test.md")).is_none());
+    }
+
+    #[test]
+    fn add_list_remove_persist_to_file() {
+        let dir = TempDir::new().unwrap();
+        let path = start(&dir);
+        let mut client = Client::connect(&path).unwrap();
+
+        let item = make_item("claude: Test", 42, "tael", None, Status::Waiting);
+        assert!(client.add(item).unwrap().is_none());
+        assert_eq!(client.list().unwrap().items.len(), 1);
+        assert_eq!(file::load(&path).unwrap().items.len(), 1);
+
+        assert_eq!(client.remove(42).unwrap()[0].msg(), "claude: Test");
+        assert!(client.remove(42).unwrap().is_empty());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn picks_up_external_file_changes() {
+        let dir = TempDir::new().unwrap();
+        let path = start(&dir);
+        let mut client = Client::connect(&path).unwrap();
+
+        let mut inbox = Inbox::new();
+        inbox.upsert(make_item("external", 1, "tael", None, Status::Working));
+        file::save(&path, &inbox).unwrap();
+
+        assert_eq!(client.list().unwrap().items[0].msg(), "external");
+    }
+
+    #[test]
+    fn rejects_other_protocol_versions() {
+        let dir = TempDir::new().unwrap();
+        let path = start(&dir);
+        let mut stream = UnixStream::connect(socket_path(&path)).unwrap();
+        stream.write_all(b"{\"v\":99,\"op\":\"list\"}\n").unwrap();
+
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line).unwrap();
+        let response: Response = serde_json::from_str(&line).unwrap();
+        assert!(!response.ok);
+        assert!(response.error.unwrap().contains("version 99"));
+    }
+
+    #[test]
+    fn subscribers_receive_changes() {
+        let dir = TempDir::new().unwrap();
+        let path = start(&dir);
+        let mut updates = Client::connect(&path).unwrap().subscribe().unwrap();
+        assert!(updates.next().unwrap().unwrap().is_empty());
+
+        let mut client = Client::connect(&path).unwrap();
+        client
+            .add(make_item("hello", 7, "tael", None, Status::Waiting))
+            .unwrap();
+        let inbox = updates.next().unwrap().unwrap();
+        assert_eq!(inbox.items[0].pane_id(), Some(7));
+    }
+
+    #[test]
+    fn stalled_subscribers_do_not_block_requests() {
+        let dir = TempDir::new().unwrap();
+        let path = start(&dir);
+        // Subscribe but never read, so the socket buffer fills up
+        let mut stalled = UnixStream::connect(socket_path(&path)).unwrap();
+        stalled
+            .write_all(b"{\"v\":1,\"op\":\"subscribe\"}\n")
+            .unwrap();
+
+        let mut client = Client::connect(&path).unwrap();
+        let msg = "x".repeat(1024);
+        for pane in 0..200 {
+            client
+                .add(make_item(&msg, pane, "tael", None, Status::Working))
+                .unwrap();
+        }
+        assert_eq!(client.list().unwrap().items.len(), 200);
+    }
+
+    #[test]
+    fn store_falls_back_to_file_when_daemon_goes_away() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.md");
+        // A "daemon" that accepts the connection and then hangs up
+        let listener = UnixListener::bind(socket_path(&path)).unwrap();
+        std::thread::spawn(move || drop(listener.accept()));
+
+        let mut store = Store::open(&path);
+        assert!(matches!(store, Store::Daemon(_)));
+        store
+            .add(make_item("hello", 7, "tael", None, Status::Waiting))
+            .unwrap();
+        assert!(matches!(store, Store::File(_)));
+        assert_eq!(file::load(&path).unwrap().items.len(), 1);
+    }
+
+    #[test]
+    fn dropping_the_server_removes_its_socket() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.md");
+        let server = Server::bind(&path).unwrap();
+        assert!(socket_path(&path).exists());
+        drop(server);
+        assert!(!socket_path(&path).exists());
+    }
+
+    #[test]
+    fn escalates_overdue_items_in_the_background() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.md");
+        let log = dir.path().join("notifications.log");
+        let mut item = make_item("claude: Deploy?", 1, "tael", None, Status::Waiting);
+        item.attrs
+            .insert("priority".to_string(), "urgent".to_string());
+        item.attrs.insert("since".to_string(), "0".to_string());
+        let mut inbox = Inbox::new();
+        inbox.upsert(item);
+        file::save(&path, &inbox).unwrap();
+
+        let server = Server::bind(&path).unwrap();
+        server.escalate_every(
+            Duration::from_millis(10),
+            EscalateConfig {
+                after: 60,
+                actions: vec![escalate::Action::Notify],
+            },
+            NotifyConfig {
+                command: Some(format!("sh -c 'echo \"$0\" >> {}' {{msg}}", log.display())),
+                ..Default::default()
+            },
+        );
+        assert_eq!(crate::test_utils::read_log(&log, 1), "claude: Deploy?\n");
+    }
+}
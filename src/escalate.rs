@@ -0,0 +1,242 @@
+//! Escalation for urgent items left waiting too long
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::notify::{self, NotifyConfig};
+use crate::{Inbox, InboxItem, Priority, Status};
+
+/// What happens when an urgent item goes unanswered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Ring the terminal bell (from the TUI)
+    Bell,
+    /// Send a desktop notification (once per waiting stint)
+    Notify,
+    /// Highlight the TUI status line and `tael status` output
+    Flash,
+}
+
+impl std::str::FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "bell" => Ok(Self::Bell),
+            "notify" => Ok(Self::Notify),
+            "flash" => Ok(Self::Flash),
+            other => Err(format!(
+                "invalid escalation '{}': use bell, notify or flash",
+                other
+            )),
+        }
+    }
+}
+
+/// Escalation settings
+#[derive(Debug, Clone)]
+pub struct EscalateConfig {
+    /// Seconds an urgent item may wait before escalating
+    pub after: u64,
+    /// What to do then (empty disables escalation)
+    pub actions: Vec<Action>,
+}
+
+impl Default for EscalateConfig {
+    fn default() -> Self {
+        Self {
+            after: 10 * 60,
+            actions: vec![Action::Bell, Action::Flash],
+        }
+    }
+}
+
+impl EscalateConfig {
+    /// Load from TAEL_ESCALATE (comma-separated actions, or "off") and
+    /// TAEL_ESCALATE_AFTER (a duration); invalid values are ignored
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            after: env::var("TAEL_ESCALATE_AFTER")
+                .ok()
+                .and_then(|v| crate::time::parse_duration(&v))
+                .unwrap_or(defaults.after),
+            actions: match env::var("TAEL_ESCALATE") {
+                Ok(v) => v.split(',').filter_map(|a| a.parse().ok()).collect(),
+                Err(_) => defaults.actions,
+            },
+        }
+    }
+
+    /// Whether an action is enabled
+    pub fn has(&self, action: Action) -> bool {
+        self.actions.contains(&action)
+    }
+}
+
+/// Urgent items that have been waiting for at least `config.after` seconds
+/// (and aren't snoozed)
+pub fn overdue<'a>(config: &EscalateConfig, inbox: &'a Inbox, now: u64) -> Vec<&'a InboxItem> {
+    if config.actions.is_empty() {
+        return Vec::new();
+    }
+    inbox
+        .items
+        .iter()
+        .filter(|i| i.status == Status::Waiting && i.priority() == Priority::Urgent)
+        .filter(|i| !i.is_snoozed(now))
+        .filter(|i| {
+            i.since()
+                .is_some_and(|since| now.saturating_sub(since) >= config.after)
+        })
+        .collect()
+}
+
+/// Key identifying one waiting stint of an item: a new `since` escalates again
+pub fn stint(item: &InboxItem) -> String {
+    let identity = item
+        .id()
+        .map(|id| format!("id:{}", id))
+        .or_else(|| item.get("pane").map(|p| format!("pane:{}", p)))
+        .unwrap_or_else(|| format!("msg:{}", item.msg()));
+    format!("{}@{}", identity, item.get("since").unwrap_or_default())
+}
+
+/// Escalation state file for an inbox file (`foo.md` -> `foo.escalate.json`)
+pub fn state_path(inbox_path: &Path) -> PathBuf {
+    let stem = inbox_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("inbox");
+    inbox_path.with_file_name(format!("{}.escalate.json", stem))
+}
+
+/// Send a notification for each overdue item not notified about yet (if the
+/// notify action is enabled). Returns the items notified about.
+pub fn check(
+    config: &EscalateConfig,
+    notify_config: &NotifyConfig,
+    inbox_path: &Path,
+    inbox: &Inbox,
+    now: u64,
+) -> Result<Vec<InboxItem>, String> {
+    if !config.has(Action::Notify) {
+        return Ok(Vec::new());
+    }
+    let due = overdue(config, inbox, now);
+    let state_path = state_path(inbox_path);
+    if due.is_empty() && !state_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let notified: HashMap<String, u64> = fs::read_to_string(&state_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+
+    // Keep only stints still overdue, so answered items can escalate again
+    let mut state = HashMap::new();
+    let mut sent = Vec::new();
+    let mut result = Ok(());
+    for item in due {
+        let key = stint(item);
+        match notified.get(&key) {
+            Some(&ts) => {
+                state.insert(key, ts);
+            }
+            None => {
+                if let Err(e) = notify::send(notify_config, item) {
+                    result = Err(e);
+                }
+                state.insert(key, now);
+                sent.push(item.clone());
+            }
+        }
+    }
+
+    if state != notified {
+        if state.is_empty() {
+            let _ = fs::remove_file(&state_path);
+        } else if let Ok(json) = serde_json::to_string(&state) {
+            let _ = fs::write(&state_path, json);
+        }
+    }
+    result.map(|_| sent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_item, read_log};
+    use tempfile::TempDir;
+
+    fn urgent(pane: u32, since: u64) -> InboxItem {
+        let mut item = make_item("claude: Deploy?", pane, "tael", None, Status::Waiting);
+        item.attrs
+            .insert("priority".to_string(), "urgent".to_string());
+        item.attrs.insert("since".to_string(), since.to_string());
+        item
+    }
+
+    #[test]
+    fn only_urgent_waiting_items_past_the_threshold() {
+        let mut working = urgent(3, 0);
+        working.status = Status::Working;
+        let mut high = urgent(4, 0);
+        high.attrs
+            .insert("priority".to_string(), "high".to_string());
+        let inbox = Inbox {
+            items: vec![urgent(1, 0), urgent(2, 500), working, high],
+        };
+
+        let config = EscalateConfig::default();
+        let panes: Vec<_> = overdue(&config, &inbox, 600)
+            .iter()
+            .filter_map(|i| i.pane_id())
+            .collect();
+        assert_eq!(panes, vec![1]);
+
+        let off = EscalateConfig {
+            actions: Vec::new(),
+            ..config
+        };
+        assert!(overdue(&off, &inbox, 600).is_empty());
+    }
+
+    #[test]
+    fn notifies_once_per_waiting_stint() {
+        let dir = TempDir::new().unwrap();
+        let inbox_path = dir.path().join("test.md");
+        let log = dir.path().join("notifications.log");
+        let notify_config = NotifyConfig {
+            command: Some(format!("sh -c 'echo \"$0\" >> {}' {{msg}}", log.display())),
+            ..Default::default()
+        };
+        let config = EscalateConfig {
+            after: 60,
+            actions: vec![Action::Notify],
+        };
+        let mut inbox = Inbox {
+            items: vec![urgent(1, 1000)],
+        };
+
+        let check = |inbox: &Inbox, now| {
+            check(&config, &notify_config, &inbox_path, inbox, now)
+                .unwrap()
+                .len()
+        };
+        assert_eq!(check(&inbox, 1030), 0);
+        assert_eq!(check(&inbox, 1060), 1);
+        assert_eq!(check(&inbox, 1200), 0);
+        assert_eq!(read_log(&log, 1), "claude: Deploy?\n");
+
+        // Answered, then waiting again: a new stint escalates again
+        inbox.items[0].status = Status::Working;
+        assert_eq!(check(&inbox, 1300), 0);
+        assert!(!state_path(&inbox_path).exists());
+        inbox.items[0] = urgent(1, 1300);
+        assert_eq!(check(&inbox, 1400), 1);
+    }
+}
@@ -0,0 +1,197 @@
+//! Export the inbox in machine-friendly formats
+
+use crate::{render, Inbox};
+
+/// Formats accepted by `tael list --format` (besides templates)
+pub const FORMATS: &[&str] = &["json", "jsonl", "csv", "tsv", "yaml", "markdown"];
+
+/// Render the inbox in a named format, or as a template like
+/// "{status} {proj}: {msg}" (one line per item) when `format` contains `{`.
+pub fn export(inbox: &Inbox, format: &str) -> Result<String, String> {
+    let output = match format {
+        "json" => serde_json::to_string_pretty(inbox).map_err(|e| e.to_string())? + "\n",
+        "jsonl" => inbox
+            .items
+            .iter()
+            .map(|item| serde_json::to_string(item).map(|s| s + "\n"))
+            .collect::<Result<String, _>>()
+            .map_err(|e| e.to_string())?,
+        "csv" => delimited(inbox, ',', csv_field),
+        "tsv" => delimited(inbox, '\t', tsv_field),
+        "yaml" => yaml(inbox),
+        "markdown" | "md" => render::render(inbox),
+        template if template.contains('{') => inbox
+            .items
+            .iter()
+            .map(|item| item.fill_template(template) + "\n")
+            .collect(),
+        other => {
+            return Err(format!(
+                "unknown format '{}': use {} or a template like '{{status}} {{proj}}: {{msg}}'",
+                other,
+                FORMATS.join(", ")
+            ))
+        }
+    };
+    Ok(output)
+}
+
+/// Column names: status, msg and the conventional attrs first, then the rest
+/// alphabetically (union over all items)
+fn columns(inbox: &Inbox) -> Vec<String> {
+    let leading = ["msg", "pane", "proj", "branch"];
+    let mut rest: Vec<&String> = inbox
+        .items
+        .iter()
+        .flat_map(|i| i.attrs.keys())
+        .filter(|k| !leading.contains(&k.as_str()))
+        .collect();
+    rest.sort();
+    rest.dedup();
+
+    std::iter::once("status".to_string())
+        .chain(leading.iter().map(|k| k.to_string()))
+        .chain(rest.into_iter().cloned())
+        .collect()
+}
+
+/// Header row plus one row per item
+fn delimited(inbox: &Inbox, sep: char, field: fn(&str) -> String) -> String {
+    let columns = columns(inbox);
+    let sep = sep.to_string();
+    let mut out = columns
+        .iter()
+        .map(|c| field(c))
+        .collect::<Vec<_>>()
+        .join(&sep);
+    out.push('\n');
+    for item in &inbox.items {
+        let row: Vec<String> = columns
+            .iter()
+            .map(|c| match c.as_str() {
+                "status" => field(item.status.as_str()),
+                key => field(item.get(key).unwrap_or("")),
+            })
+            .collect();
+        out.push_str(&row.join(&sep));
+        out.push('\n');
+    }
+    out
+}
+
+/// Quote a CSV field if needed (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// TSV has no quoting: replace tabs and newlines with spaces
+fn tsv_field(value: &str) -> String {
+    value.replace(['\t', '\n', '\r'], " ")
+}
+
+/// YAML list of items. Strings are emitted as JSON strings, which are
+/// valid YAML and need no further escaping rules.
+fn yaml(inbox: &Inbox) -> String {
+    if inbox.is_empty() {
+        return "[]\n".to_string();
+    }
+    let quote = |s: &str| serde_json::to_string(s).unwrap_or_default();
+    let mut out = String::new();
+    for item in &inbox.items {
+        out.push_str(&format!("- status: {}\n  attrs:\n", item.status.as_str()));
+        let mut keys: Vec<_> = item.attrs.keys().collect();
+        keys.sort();
+        for key in keys {
+            out.push_str(&format!(
+                "    {}: {}\n",
+                quote(key),
+                quote(&item.attrs[key])
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::make_item, Status};
+
+    fn sample_inbox() -> Inbox {
+        let mut item = make_item("Fix \"quotes\", commas", 42, "tael", None, Status::Waiting);
+        item.attrs
+            .insert("type".to_string(), "permission".to_string());
+        Inbox {
+            items: vec![
+                item,
+                make_item(
+                    "indexer: Running",
+                    5,
+                    "crucible",
+                    Some("main"),
+                    Status::Working,
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn jsonl_one_item_per_line() {
+        let output = export(&sample_inbox(), "jsonl").unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let item: crate::InboxItem = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(item.pane_id(), Some(5));
+    }
+
+    #[test]
+    fn csv_with_header_and_quoting() {
+        let output = export(&sample_inbox(), "csv").unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines[0], "status,msg,pane,proj,branch,type");
+        assert_eq!(
+            lines[1],
+            "waiting,\"Fix \"\"quotes\"\", commas\",42,tael,,permission"
+        );
+        assert_eq!(lines[2], "working,indexer: Running,5,crucible,main,");
+    }
+
+    #[test]
+    fn tsv_rows() {
+        let output = export(&sample_inbox(), "tsv").unwrap();
+        assert!(output.starts_with("status\tmsg\tpane\tproj\tbranch\ttype\n"));
+        assert!(output.contains("working\tindexer: Running\t5\tcrucible\tmain\t\n"));
+    }
+
+    #[test]
+    fn yaml_items() {
+        let output = export(&sample_inbox(), "yaml").unwrap();
+        assert!(output.starts_with("- status: waiting\n  attrs:\n"));
+        assert!(output.contains("    \"msg\": \"Fix \\\"quotes\\\", commas\"\n"));
+        assert_eq!(export(&Inbox::new(), "yaml").unwrap(), "[]\n");
+    }
+
+    #[test]
+    fn markdown_matches_render() {
+        let inbox = sample_inbox();
+        assert_eq!(export(&inbox, "markdown").unwrap(), render::render(&inbox));
+    }
+
+    #[test]
+    fn template_lines() {
+        let output = export(&sample_inbox(), "{status} {proj}: {msg}").unwrap();
+        assert_eq!(
+            output,
+            "waiting tael: Fix \"quotes\", commas\nworking crucible: indexer: Running\n"
+        );
+    }
+
+    #[test]
+    fn unknown_format_errors() {
+        assert!(export(&sample_inbox(), "xml").is_err());
+    }
+}
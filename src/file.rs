@@ -1,9 +1,11 @@
 //! File operations for inbox
 
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::process::Command;
 use std::{env, fs};
 
-use crate::{parse, render, Inbox};
+use crate::{history, parse, render, Inbox};
 
 /// Get the default inbox file path
 pub fn default_path() -> PathBuf {
@@ -12,23 +14,172 @@ pub fn default_path() -> PathBuf {
         return PathBuf::from(path);
     }
 
-    // Build path in data directory
-    let base = dirs::data_local_dir()
+    data_dir().join(format!("{}.md", current_session()))
+}
+
+/// Directory holding per-session inbox files
+pub fn data_dir() -> PathBuf {
+    dirs::data_local_dir()
         .unwrap_or_else(|| PathBuf::from("/tmp"))
-        .join("tael");
+        .join("tael")
+}
+
+/// Session name for the current multiplexer session (for isolation).
+/// tmux sessions are namespaced as `tmux-<name>` so they can't collide
+/// with Zellij sessions of the same name.
+pub fn current_session() -> String {
+    if let Ok(name) = env::var("ZELLIJ_SESSION_NAME") {
+        return name;
+    }
+    // Only ask tmux when we're actually inside it
+    if let Ok(tmux) = env::var("TMUX") {
+        let pane = env::var("TMUX_PANE").ok();
+        return tmux_session(pane.as_deref())
+            .map(|name| tmux_session_key(&name))
+            .or_else(|| session_from_tmux_env(&tmux))
+            .unwrap_or_else(|| "default".to_string());
+    }
+    "default".to_string()
+}
 
-    // Use session name if available (for multiplexer isolation)
-    let session = env::var("ZELLIJ_SESSION_NAME")
-        .or_else(|_| env::var("TMUX_PANE").map(|p| format!("tmux-{}", p)))
-        .unwrap_or_else(|_| "default".to_string());
+/// Session key (and inbox file stem) for a tmux session name or `$id`
+fn tmux_session_key(target: &str) -> String {
+    format!("tmux-{}", target)
+}
 
-    base.join(format!("{}.md", session))
+/// Name to hand to the multiplexer for a session key: strips the `tmux-`
+/// namespace (`tmux-work` -> `work`, `tmux-$3` -> `$3`)
+pub fn session_target(session: &str) -> &str {
+    session.strip_prefix("tmux-").unwrap_or(session)
+}
+
+/// Name of the tmux session a pane (or the current client) belongs to
+pub fn tmux_session(pane: Option<&str>) -> Option<String> {
+    let mut cmd = Command::new("tmux");
+    cmd.arg("display-message").arg("-p");
+    if let Some(pane) = pane {
+        cmd.arg("-t").arg(pane);
+    }
+    let output = cmd.arg("#S").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+/// Fallback when tmux can't be asked: $TMUX is "socket,pid,session_id",
+/// which only carries the numeric session id (a valid `-t $id` target)
+fn session_from_tmux_env(tmux: &str) -> Option<String> {
+    let id = tmux.rsplit(',').next()?.trim();
+    (!id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+        .then(|| tmux_session_key(&format!("${}", id)))
+}
+
+/// Marker left in the data directory once pane files have been migrated
+const TMUX_MIGRATED: &str = ".tmux-panes-migrated";
+
+/// Merge inbox files from older versions, which were keyed by tmux pane
+/// (`tmux-%N.md`), into per-session files. `session_of` maps a pane to its
+/// session name; files of panes that no longer exist are left in place.
+/// Runs once per directory. Returns the number of files merged.
+pub fn migrate_tmux_pane_files(
+    dir: &Path,
+    session_of: impl Fn(&str) -> Option<String>,
+) -> Result<usize, std::io::Error> {
+    let marker = dir.join(TMUX_MIGRATED);
+    if marker.exists() || !dir.exists() {
+        return Ok(0);
+    }
+
+    let mut merged = 0;
+    for old in session_paths(dir)? {
+        let name = session_name(&old);
+        let Some(pane) = name.strip_prefix("tmux-").filter(|p| p.starts_with('%')) else {
+            continue;
+        };
+        let Some(session) = session_of(pane) else {
+            continue;
+        };
+        let target = dir.join(format!("{}.md", tmux_session_key(&session)));
+
+        let mut inbox = load(&target)?;
+        for item in load(&old)?.items {
+            inbox.upsert(item);
+        }
+        save(&target, &inbox)?;
+
+        // Carry the event history along
+        let old_history = history::path_for(&old);
+        if let Ok(content) = fs::read_to_string(&old_history) {
+            let mut file = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(history::path_for(&target))?;
+            file.write_all(content.as_bytes())?;
+            fs::remove_file(&old_history)?;
+        }
+
+        fs::remove_file(&old)?;
+        merged += 1;
+    }
+    fs::write(&marker, "")?;
+    Ok(merged)
+}
+
+/// Session name of an inbox file (its file stem)
+pub fn session_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Directory holding an inbox file and its sibling sessions ("." for a bare
+/// relative name like `team.md`)
+pub fn sessions_dir(inbox_path: &Path) -> &Path {
+    match inbox_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    }
+}
+
+/// All inbox files in a directory, sorted by name
+pub fn session_paths(dir: &Path) -> Result<Vec<PathBuf>, std::io::Error> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// Load every inbox file in a directory into one inbox, tagging each item
+/// with a "session" attr (items stay grouped by session)
+pub fn load_all(dir: &Path) -> Result<Inbox, std::io::Error> {
+    let mut all = Inbox::new();
+    for path in session_paths(dir)? {
+        let session = session_name(&path);
+        for mut item in load(&path)?.items {
+            item.attrs.insert("session".to_string(), session.clone());
+            all.items.push(item);
+        }
+    }
+    Ok(all)
 }
 
 /// Load inbox from file (returns empty inbox if file doesn't exist)
 pub fn load(path: &Path) -> Result<Inbox, std::io::Error> {
     match fs::read_to_string(path) {
-        Ok(content) => Ok(parse::parse(&content)),
+        Ok(content) => {
+            let mut inbox = parse::parse(&content);
+            inbox.ensure_ids();
+            Ok(inbox)
+        }
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Inbox::new()),
         Err(e) => Err(e),
     }
@@ -90,6 +241,33 @@ mod tests {
         assert_eq!(loaded.items[0].pane_id(), Some(42));
     }
 
+    #[test]
+    fn load_all_tags_sessions() {
+        let dir = TempDir::new().unwrap();
+        for (session, pane) in [("beta", 2), ("alpha", 1)] {
+            let mut inbox = Inbox::new();
+            inbox.upsert(make_item("hi", pane, "tael", None, Status::Waiting));
+            save(&dir.path().join(format!("{}.md", session)), &inbox).unwrap();
+        }
+        // Non-inbox files next to them are ignored
+        fs::write(dir.path().join("alpha.history.jsonl"), "{}\n").unwrap();
+
+        let inbox = load_all(dir.path()).unwrap();
+        assert_eq!(inbox.items.len(), 2);
+        assert_eq!(inbox.items[0].get("session"), Some("alpha"));
+        assert_eq!(inbox.items[1].get("session"), Some("beta"));
+        assert_eq!(inbox.items[1].pane_id(), Some(2));
+
+        assert!(load_all(&dir.path().join("missing")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn sessions_dir_of_relative_paths() {
+        assert_eq!(sessions_dir(Path::new("team.md")), Path::new("."));
+        assert_eq!(sessions_dir(Path::new("inbox/team.md")), Path::new("inbox"));
+        assert_eq!(sessions_dir(Path::new("/tmp/team.md")), Path::new("/tmp"));
+    }
+
     #[test]
     fn save_empty_deletes_file() {
         let dir = TempDir::new().unwrap();
@@ -104,4 +282,53 @@ mod tests {
         save(&path, &inbox).unwrap();
         assert!(!path.exists());
     }
+
+    #[test]
+    fn tmux_env_fallback_uses_session_id() {
+        assert_eq!(
+            session_from_tmux_env("/tmp/tmux-1000/default,4242,3"),
+            Some("tmux-$3".to_string())
+        );
+        assert_eq!(session_from_tmux_env("garbage"), None);
+        assert_eq!(session_target("tmux-$3"), "$3");
+        assert_eq!(session_target("tmux-work"), "work");
+        assert_eq!(session_target("zellij-dev"), "zellij-dev");
+    }
+
+    #[test]
+    fn migrate_pane_files_into_sessions() {
+        let dir = TempDir::new().unwrap();
+        let pane_file = |pane: u32, msg: &str| {
+            let mut inbox = Inbox::new();
+            inbox.upsert(make_item(msg, pane, "tael", None, Status::Waiting));
+            save(&dir.path().join(format!("tmux-%{}.md", pane)), &inbox).unwrap();
+        };
+        pane_file(1, "one");
+        pane_file(2, "two");
+        pane_file(3, "gone");
+        fs::write(dir.path().join("tmux-%1.history.jsonl"), "{}\n").unwrap();
+
+        let merged = migrate_tmux_pane_files(dir.path(), |pane| match pane {
+            "%1" | "%2" => Some("work".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(merged, 2);
+
+        let work = load(&dir.path().join("tmux-work.md")).unwrap();
+        assert_eq!(work.items.len(), 2);
+        assert!(dir.path().join("tmux-work.history.jsonl").exists());
+        assert!(!dir.path().join("tmux-%1.md").exists());
+        assert!(!dir.path().join("tmux-%1.history.jsonl").exists());
+        // Dead panes have no session to go to
+        assert!(dir.path().join("tmux-%3.md").exists());
+
+        // Only done once
+        pane_file(4, "later");
+        assert_eq!(
+            migrate_tmux_pane_files(dir.path(), |_| Some("work".to_string())).unwrap(),
+            0
+        );
+        assert!(dir.path().join("tmux-%4.md").exists());
+    }
 }
@@ -0,0 +1,209 @@
+//! Append-only event history for inbox changes
+//!
+//! Every add/update/remove/focus is appended as one JSON object per line to
+//! a `.history.jsonl` file next to the inbox file.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::InboxItem;
+
+/// Kind of inbox event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    /// New item added
+    Add,
+    /// Existing item replaced
+    Update,
+    /// Item removed
+    Remove,
+    /// Item's pane focused from the TUI
+    Focus,
+}
+
+impl EventKind {
+    /// Lowercase name for display
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Update => "update",
+            Self::Remove => "remove",
+            Self::Focus => "focus",
+        }
+    }
+}
+
+/// A single history entry: the item as it was when the event happened
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    /// Unix seconds
+    pub ts: u64,
+    pub event: EventKind,
+    #[serde(flatten)]
+    pub item: InboxItem,
+}
+
+impl Event {
+    /// Create an event for an item at the current time
+    pub fn new(event: EventKind, item: &InboxItem) -> Self {
+        Self {
+            ts: crate::time::now(),
+            event,
+            item: item.clone(),
+        }
+    }
+}
+
+/// History file path for an inbox file (`foo.md` -> `foo.history.jsonl`)
+pub fn path_for(inbox_path: &Path) -> PathBuf {
+    let stem = inbox_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("inbox");
+    inbox_path.with_file_name(format!("{}.history.jsonl", stem))
+}
+
+/// Append events to the history file (creates parent dirs)
+pub fn append(path: &Path, events: &[Event]) -> Result<(), std::io::Error> {
+    if events.is_empty() {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut content = String::new();
+    for event in events {
+        content.push_str(&serde_json::to_string(event)?);
+        content.push('\n');
+    }
+    // Single write so concurrent appenders don't interleave lines
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(content.as_bytes())
+}
+
+/// Record events next to an inbox file, ignoring failures.
+/// History is best-effort and must never block inbox updates.
+pub fn record(inbox_path: &Path, events: &[Event]) {
+    let _ = append(&path_for(inbox_path), events);
+}
+
+/// Load all events (returns empty if file doesn't exist, skips malformed lines)
+pub fn load(path: &Path) -> Result<Vec<Event>, std::io::Error> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Criteria for selecting events
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub proj: Option<String>,
+    pub agent: Option<String>,
+    /// Inclusive lower bound (unix seconds)
+    pub since: Option<u64>,
+    /// Exclusive upper bound (unix seconds)
+    pub until: Option<u64>,
+}
+
+impl Filter {
+    /// Check whether an event matches every set criterion
+    pub fn matches(&self, event: &Event) -> bool {
+        if let Some(ref proj) = self.proj {
+            if event.item.proj() != Some(proj.as_str()) {
+                return false;
+            }
+        }
+        if let Some(ref agent) = self.agent {
+            if event.item.agent() != Some(agent.as_str()) {
+                return false;
+            }
+        }
+        if self.since.is_some_and(|since| event.ts < since) {
+            return false;
+        }
+        if self.until.is_some_and(|until| event.ts >= until) {
+            return false;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::make_item, Status};
+    use tempfile::TempDir;
+
+    #[test]
+    fn path_next_to_inbox() {
+        let path = path_for(Path::new("/data/tael/main.md"));
+        assert_eq!(path, PathBuf::from("/data/tael/main.history.jsonl"));
+    }
+
+    #[test]
+    fn append_and_load_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nested/test.history.jsonl");
+
+        let item = make_item("claude: Test", 42, "tael", None, Status::Waiting);
+        append(&path, &[Event::new(EventKind::Add, &item)]).unwrap();
+        append(&path, &[Event::new(EventKind::Remove, &item)]).unwrap();
+
+        let events = load(&path).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event, EventKind::Add);
+        assert_eq!(events[1].event, EventKind::Remove);
+        assert_eq!(events[1].item.pane_id(), Some(42));
+        assert_eq!(events[1].item.status, Status::Waiting);
+    }
+
+    #[test]
+    fn load_skips_malformed_lines() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.history.jsonl");
+        fs::write(
+            &path,
+            "not json\n{\"ts\":5,\"event\":\"add\",\"attrs\":{},\"status\":\"waiting\"}\n",
+        )
+        .unwrap();
+        let events = load(&path).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].ts, 5);
+    }
+
+    #[test]
+    fn filter_by_proj_agent_and_time() {
+        let mut event = Event::new(
+            EventKind::Add,
+            &make_item("claude: Test", 1, "tael", None, Status::Waiting),
+        );
+        event.ts = 100;
+
+        assert!(Filter::default().matches(&event));
+        let by_proj = Filter {
+            proj: Some("tael".to_string()),
+            ..Default::default()
+        };
+        assert!(by_proj.matches(&event));
+        let by_agent = Filter {
+            agent: Some("codex".to_string()),
+            ..Default::default()
+        };
+        assert!(!by_agent.matches(&event));
+        let by_time = Filter {
+            since: Some(50),
+            until: Some(100),
+            ..Default::default()
+        };
+        assert!(!by_time.matches(&event));
+    }
+}
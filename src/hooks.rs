@@ -0,0 +1,206 @@
+//! User-defined commands run when the inbox changes
+
+use std::env;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::watch::Change;
+use crate::InboxItem;
+
+/// Inbox event a hook can be attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    Add,
+    Update,
+    Remove,
+    Focus,
+}
+
+impl HookEvent {
+    /// Lowercase name, available to hooks as {event}
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Update => "update",
+            Self::Remove => "remove",
+            Self::Focus => "focus",
+        }
+    }
+
+    /// Event and item for an inbox change (e.g. one made by undo)
+    pub fn for_change(change: &Change) -> (Self, &InboxItem) {
+        match change {
+            Change::Added(item) => (Self::Add, item),
+            Change::Updated(item) => (Self::Update, item),
+            Change::Removed(item) => (Self::Remove, item),
+        }
+    }
+}
+
+/// Hook commands. Placeholders: {event}, {status}, {pane_id} and any attr
+/// such as {msg} or {proj} (missing attrs expand to an empty string).
+#[derive(Debug, Clone)]
+pub struct HookConfig {
+    /// Run when an item is added or updated
+    pub on_add: Option<String>,
+    /// Run when an item is removed
+    pub on_remove: Option<String>,
+    /// Run when an item's pane is focused from the TUI
+    pub on_focus: Option<String>,
+    /// Kill hooks still running after this long
+    pub timeout: Duration,
+}
+
+impl Default for HookConfig {
+    fn default() -> Self {
+        Self {
+            on_add: None,
+            on_remove: None,
+            on_focus: None,
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl HookConfig {
+    /// Load from TAEL_ON_ADD, TAEL_ON_REMOVE, TAEL_ON_FOCUS and TAEL_HOOK_TIMEOUT
+    pub fn from_env() -> Self {
+        let cmd = |name: &str| env::var(name).ok().filter(|c| !c.trim().is_empty());
+        Self {
+            on_add: cmd("TAEL_ON_ADD"),
+            on_remove: cmd("TAEL_ON_REMOVE"),
+            on_focus: cmd("TAEL_ON_FOCUS"),
+            timeout: env::var("TAEL_HOOK_TIMEOUT")
+                .ok()
+                .and_then(|v| crate::time::parse_duration(&v))
+                .map(Duration::from_secs)
+                .unwrap_or(Self::default().timeout),
+        }
+    }
+
+    /// Command template for an event
+    pub fn command(&self, event: HookEvent) -> Option<&str> {
+        match event {
+            HookEvent::Add | HookEvent::Update => self.on_add.as_deref(),
+            HookEvent::Remove => self.on_remove.as_deref(),
+            HookEvent::Focus => self.on_focus.as_deref(),
+        }
+    }
+
+    /// Start the hook for an event in the background (no-op if none is set).
+    /// Returns immediately; the hook is killed after the configured timeout.
+    pub fn fire(&self, event: HookEvent, item: &InboxItem) -> Result<(), String> {
+        let Some(template) = self.command(event) else {
+            return Ok(());
+        };
+        let args = expand(template, event, item)?;
+
+        // Re-run ourselves as a detached supervisor that enforces the timeout,
+        // so the caller (e.g. `tael add` in an agent hook) never waits
+        let exe = env::current_exe().map_err(|e| format!("Failed to locate tael: {}", e))?;
+        Command::new(exe)
+            .arg("hook-exec")
+            .arg("--timeout")
+            .arg(self.timeout.as_secs().to_string())
+            .arg("--")
+            .args(&args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to start hook: {}", e))?;
+        Ok(())
+    }
+}
+
+/// Split a hook template into arguments and fill in placeholders.
+/// Splitting happens first, so values containing spaces stay one argument.
+pub fn expand(template: &str, event: HookEvent, item: &InboxItem) -> Result<Vec<String>, String> {
+    let parts =
+        shell_words::split(template).map_err(|e| format!("Failed to parse hook command: {}", e))?;
+    if parts.is_empty() {
+        return Err("Empty hook command".to_string());
+    }
+
+    Ok(parts
+        .iter()
+        .map(|part| item.fill_template(&part.replace("{event}", event.as_str())))
+        .collect())
+}
+
+/// Run a command to completion, killing it once `timeout` has passed
+pub fn run_with_timeout(args: &[String], timeout: Duration) -> Result<(), String> {
+    let (cmd, rest) = args.split_first().ok_or("Empty hook command")?;
+    let mut child = Command::new(cmd)
+        .args(rest)
+        .spawn()
+        .map_err(|e| format!("Failed to execute hook: {}", e))?;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return Ok(()),
+            Ok(Some(status)) => return Err(format!("Hook exited with: {}", status)),
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("Hook timed out after {}s", timeout.as_secs()));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(20)),
+            Err(e) => return Err(format!("Failed to wait for hook: {}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::make_item, Status};
+
+    #[test]
+    fn expand_placeholders_per_argument() {
+        let item = make_item("claude: Needs input", 42, "tael", None, Status::Waiting);
+        let args = expand(
+            "notify --title '{proj} ({status})' {msg} pane={pane_id} {branch} {event}",
+            HookEvent::Add,
+            &item,
+        )
+        .unwrap();
+        assert_eq!(
+            args,
+            vec![
+                "notify",
+                "--title",
+                "tael (waiting)",
+                "claude: Needs input",
+                "pane=42",
+                "",
+                "add"
+            ]
+        );
+    }
+
+    #[test]
+    fn command_per_event() {
+        let config = HookConfig {
+            on_add: Some("add".to_string()),
+            on_remove: Some("remove".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(config.command(HookEvent::Update), Some("add"));
+        assert_eq!(config.command(HookEvent::Remove), Some("remove"));
+        assert_eq!(config.command(HookEvent::Focus), None);
+    }
+
+    #[test]
+    fn run_with_timeout_kills_slow_hooks() {
+        let ok = vec!["true".to_string()];
+        assert!(run_with_timeout(&ok, Duration::from_secs(5)).is_ok());
+
+        let slow = vec!["sleep".to_string(), "5".to_string()];
+        let started = Instant::now();
+        let err = run_with_timeout(&slow, Duration::from_millis(100)).unwrap_err();
+        assert!(err.contains("timed out"));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,266 @@
+//! Import items from JSON, JSONL or other inbox files
+
+use std::str::FromStr;
+
+use crate::{parse, Inbox, InboxItem};
+
+/// What to do when an imported item has the same pane as an existing one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conflict {
+    /// Imported item replaces the existing one
+    Replace,
+    /// Existing item is kept, imported one skipped
+    Keep,
+    /// Whichever has the later "since" timestamp wins
+    Newest,
+}
+
+impl FromStr for Conflict {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "replace" => Ok(Self::Replace),
+            "keep" => Ok(Self::Keep),
+            "newest" | "newest-wins" => Ok(Self::Newest),
+            other => Err(format!(
+                "invalid conflict strategy '{}': use replace, keep or newest",
+                other
+            )),
+        }
+    }
+}
+
+/// Parse items from `list --json` output (`{"items": [...]}`), a JSON array,
+/// JSON lines, or a Markdown inbox file
+pub fn parse_items(content: &str) -> Result<Vec<InboxItem>, String> {
+    let trimmed = content.trim_start();
+    if trimmed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    if trimmed.starts_with('[') {
+        return serde_json::from_str(trimmed).map_err(|e| format!("invalid JSON array: {}", e));
+    }
+
+    if trimmed.starts_with('{') {
+        // A whole inbox object, else one item per line
+        if let Ok(inbox) = serde_json::from_str::<Inbox>(trimmed) {
+            return Ok(inbox.items);
+        }
+        return trimmed
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(n, line)| {
+                serde_json::from_str(line)
+                    .map_err(|e| format!("invalid JSON on line {}: {}", n + 1, e))
+            })
+            .collect();
+    }
+
+    Ok(parse::parse(content).items)
+}
+
+/// Outcome of planning an import
+#[derive(Debug, Default)]
+pub struct Plan {
+    /// Final versions of the items to store (with ids), in order
+    pub upserts: Vec<InboxItem>,
+    /// Items that will be added
+    pub added: usize,
+    /// Items that will replace existing ones
+    pub replaced: usize,
+    /// Items skipped because the existing (or an earlier imported) item won
+    pub skipped: usize,
+}
+
+/// Merge imported items into a copy of `inbox`. Items repeated in the input
+/// are resolved against each other with the same strategy, and imported items
+/// keep their own "since" so `Newest` compares the right timestamps.
+pub fn plan(inbox: &Inbox, items: Vec<InboxItem>, strategy: Conflict) -> Plan {
+    let mut merged = inbox.clone();
+    let existing = merged.items.len();
+    let mut touched: Vec<usize> = Vec::new();
+    let mut plan = Plan::default();
+
+    for item in items {
+        let idx = match merged.find(&item) {
+            None => {
+                plan.added += 1;
+                merged.items.len()
+            }
+            Some(idx) => {
+                let current = &merged.items[idx];
+                let take = match strategy {
+                    Conflict::Replace => true,
+                    Conflict::Keep => false,
+                    Conflict::Newest => item.since().unwrap_or(0) > current.since().unwrap_or(0),
+                };
+                if !take {
+                    plan.skipped += 1;
+                    continue;
+                }
+                if idx < existing && !touched.contains(&idx) {
+                    plan.replaced += 1;
+                }
+                idx
+            }
+        };
+        // A match by key or pane keeps the existing id, so storing the plan
+        // (which matches by id) replaces that item rather than adding one
+        let id = merged
+            .items
+            .get(idx)
+            .and_then(|i| i.id())
+            .map(str::to_string);
+        let since = item.get("since").map(str::to_string);
+        merged.upsert(item);
+        let attrs = &mut merged.items[idx].attrs;
+        if let Some(id) = id {
+            attrs.insert("id".to_string(), id);
+        }
+        if let Some(since) = since {
+            attrs.insert("since".to_string(), since);
+        }
+        if !touched.contains(&idx) {
+            touched.push(idx);
+        }
+    }
+
+    touched.sort_unstable();
+    plan.upserts = touched
+        .into_iter()
+        .map(|idx| merged.items[idx].clone())
+        .collect();
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::make_item, Status};
+
+    fn with_since(mut item: InboxItem, since: u64) -> InboxItem {
+        item.attrs.insert("since".to_string(), since.to_string());
+        item
+    }
+
+    #[test]
+    fn parse_list_json_output() {
+        let inbox = Inbox {
+            items: vec![make_item("hi", 1, "tael", None, Status::Waiting)],
+        };
+        let json = serde_json::to_string_pretty(&inbox).unwrap();
+        let items = parse_items(&json).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].pane_id(), Some(1));
+    }
+
+    #[test]
+    fn parse_array_and_jsonl() {
+        let array = r#"[{"attrs":{"msg":"a","pane":"1"},"status":"waiting"}]"#;
+        assert_eq!(parse_items(array).unwrap()[0].msg(), "a");
+
+        let jsonl = "{\"attrs\":{\"msg\":\"a\"},\"status\":\"waiting\"}\n\n{\"attrs\":{\"msg\":\"b\"},\"status\":\"working\"}\n";
+        let items = parse_items(jsonl).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].status, Status::Working);
+
+        let err = parse_items("{\"attrs\":{}}\nnope").unwrap_err();
+        assert!(err.contains("line 1"));
+    }
+
+    #[test]
+    fn parse_markdown_inbox() {
+        let items = parse_items("## Waiting\n\n- [ ] hello [pane:: 3]\n").unwrap();
+        assert_eq!(items[0].pane_id(), Some(3));
+        assert!(parse_items("  \n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn conflict_strategies() {
+        let inbox = Inbox {
+            items: vec![with_since(
+                make_item("old", 1, "tael", None, Status::Waiting),
+                100,
+            )],
+        };
+        let incoming = || {
+            vec![
+                with_since(make_item("new", 1, "tael", None, Status::Waiting), 50),
+                make_item("fresh", 2, "tael", None, Status::Waiting),
+            ]
+        };
+
+        let replace = plan(&inbox, incoming(), Conflict::Replace);
+        assert_eq!(
+            (replace.added, replace.replaced, replace.skipped),
+            (1, 1, 0)
+        );
+
+        let keep = plan(&inbox, incoming(), Conflict::Keep);
+        assert_eq!((keep.added, keep.replaced, keep.skipped), (1, 0, 1));
+        assert_eq!(keep.upserts[0].msg(), "fresh");
+
+        // Imported item is older than the existing one
+        let newest = plan(&inbox, incoming(), Conflict::Newest);
+        assert_eq!((newest.added, newest.replaced, newest.skipped), (1, 0, 1));
+    }
+
+    #[test]
+    fn parse_conflict_names() {
+        assert_eq!("newest-wins".parse::<Conflict>(), Ok(Conflict::Newest));
+        assert!("merge".parse::<Conflict>().is_err());
+    }
+
+    #[test]
+    fn duplicates_in_the_input_merge_and_keep_their_since() {
+        let inbox = Inbox {
+            items: vec![with_since(
+                make_item("old", 1, "tael", None, Status::Waiting),
+                100,
+            )],
+        };
+        let incoming = vec![
+            with_since(make_item("a", 2, "tael", None, Status::Waiting), 300),
+            with_since(make_item("b", 2, "tael", None, Status::Waiting), 200),
+            with_since(make_item("new", 1, "tael", None, Status::Waiting), 150),
+            with_since(make_item("newer", 1, "tael", None, Status::Waiting), 400),
+        ];
+
+        let newest = plan(&inbox, incoming, Conflict::Newest);
+        assert_eq!((newest.added, newest.replaced, newest.skipped), (1, 1, 1));
+        let msgs: Vec<_> = newest.upserts.iter().map(|i| i.msg()).collect();
+        assert_eq!(msgs, vec!["newer", "a"]);
+        assert_eq!(newest.upserts[0].since(), Some(400));
+        assert!(newest.upserts[1].id().is_some());
+    }
+
+    #[test]
+    fn pane_matches_with_another_id_replace_the_existing_item() {
+        let with_id = |mut item: InboxItem, id: &str| {
+            item.attrs.insert("id".to_string(), id.to_string());
+            item
+        };
+        let mut inbox = Inbox {
+            items: vec![with_id(
+                make_item("old", 1, "tael", None, Status::Waiting),
+                "qilm",
+            )],
+        };
+        let incoming = vec![with_id(
+            make_item("new", 1, "tael", None, Status::Waiting),
+            "xd78",
+        )];
+
+        let replace = plan(&inbox, incoming, Conflict::Replace);
+        assert_eq!((replace.added, replace.replaced), (0, 1));
+        assert_eq!(replace.upserts[0].id(), Some("qilm"));
+        for item in replace.upserts {
+            inbox.put(item);
+        }
+        assert_eq!(inbox.items.len(), 1);
+        assert_eq!(inbox.items[0].msg(), "new");
+    }
+}
@@ -4,13 +4,27 @@
 //! Named after Tael, the purple fairy from Zelda: Majora's Mask.
 
 pub mod config;
+pub mod daemon;
+pub mod escalate;
+pub mod export;
 pub mod file;
+pub mod history;
+pub mod hooks;
+pub mod import;
+pub mod notify;
 pub mod parse;
 pub mod render;
+pub mod sort;
+pub mod stats;
+pub mod statusbar;
+pub mod store;
+pub mod time;
 pub mod tui;
 pub mod types;
+pub mod undo;
+pub mod watch;
 
-pub use types::{Inbox, InboxItem, Status};
+pub use types::{Inbox, InboxItem, Priority, Status};
 
 #[cfg(test)]
 pub use types::test_utils;
@@ -5,8 +5,15 @@
 use std::path::PathBuf;
 use std::process;
 
-use clap::{Parser, Subcommand};
-use tael::{config::Config, file, Inbox, InboxItem, Status};
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use tael::hooks::{self, HookEvent};
+use tael::sort::{self, Sort};
+use tael::store::Store;
+use tael::{
+    config::Config, daemon, escalate, export, file, history, import, notify, render, stats,
+    statusbar, time, undo, watch, Inbox, InboxItem, Priority, Status,
+};
 
 #[derive(Parser)]
 #[command(name = "tael")]
@@ -21,6 +28,15 @@ struct Cli {
     #[arg(long, env = "TAEL_FOCUS_CMD", global = true)]
     focus_cmd: Option<String>,
 
+    /// Session switch command template (use {session} placeholder)
+    #[arg(long, env = "TAEL_SESSION_CMD", global = true)]
+    session_cmd: Option<String>,
+
+    /// Display order for list and the TUI: comma-separated keys (status,
+    /// age, priority or any attr), each optionally prefixed with - to reverse
+    #[arg(long, env = "TAEL_SORT", global = true, default_value = sort::DEFAULT)]
+    sort: Sort,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -40,6 +56,22 @@ enum Commands {
         /// Status: wait or work (default: wait)
         #[arg(long, short = 's', default_value = "wait")]
         status: String,
+
+        /// Add a note with this message and no pane, so it never replaces
+        /// another item (e.g. a reminder)
+        #[arg(long, value_name = "MSG", conflicts_with = "from_claude_code")]
+        note: Option<String>,
+
+        /// Update the item with the same value of this attr instead of the
+        /// same pane (e.g. --key session_id). Items with neither a key nor a
+        /// pane update the one with the same msg and proj.
+        #[arg(long, value_name = "ATTR", conflicts_with = "note")]
+        key: Option<String>,
+
+        /// Priority: low, normal, high or urgent (Claude Code permission
+        /// prompts default to high)
+        #[arg(long, short = 'p', value_name = "LEVEL")]
+        priority: Option<Priority>,
     },
 
     /// Remove an item
@@ -47,21 +79,162 @@ enum Commands {
         /// Attributes to match for removal (e.g., pane=42)
         #[arg(long = "attr", short = 'a', value_name = "KEY=VALUE")]
         attrs: Vec<String>,
+
+        /// Remove the item with this id
+        #[arg(long, conflicts_with = "attrs")]
+        id: Option<String>,
     },
 
-    /// List all items
-    List {
+    /// Hide an item from list, the TUI and status until a time passes or
+    /// its status changes
+    Snooze {
+        /// Id of the item to snooze
+        #[arg(long)]
+        id: String,
+
+        /// How long (e.g., 30m, 2h, 1d) or a unix timestamp to snooze until
+        #[arg(required_unless_present = "wake")]
+        duration: Option<String>,
+
+        /// Show the item again now
+        #[arg(long, conflicts_with = "duration")]
+        wake: bool,
+    },
+
+    /// Show one item by id
+    Show {
+        id: String,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
+    },
+
+    /// List all items
+    List {
+        /// Output as JSON (same as --format json)
+        #[arg(long, conflicts_with = "format")]
+        json: bool,
+
+        /// Output format: json, jsonl, csv, tsv, yaml, markdown, or a template
+        /// like "{status} {proj}: {msg}"
+        #[arg(long)]
+        format: Option<String>,
 
         /// Group by attribute (e.g., proj, status)
         #[arg(long, value_delimiter = ',')]
         group_by: Vec<String>,
+
+        /// Show items from every session's inbox, grouped by session
+        #[arg(long)]
+        all_sessions: bool,
+
+        /// Include snoozed items (listed in their own section)
+        #[arg(long)]
+        snoozed: bool,
+    },
+
+    /// Clear all items except pinned ones (`pinned` or `sticky` attr)
+    Clear {
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Remove items waiting or working for longer than a TTL, except pinned
+    /// ones
+    Gc {
+        /// Remove items older than this (e.g., 12h, 1d, 1w)
+        #[arg(long, default_value = "1d")]
+        older_than: String,
+    },
+
+    /// Undo the last remove, clear, gc, import or TUI delete/edit/status change
+    Undo,
+
+    /// Redo the last undone action
+    Redo,
+
+    /// Import items from JSON (array, `list --json` output, JSON lines) or
+    /// another inbox file
+    Import {
+        /// File to read (default: stdin)
+        input: Option<PathBuf>,
+
+        /// When an item already exists (same id, key or pane): replace, keep
+        /// or newest
+        #[arg(long, default_value = "replace")]
+        on_conflict: String,
+    },
+
+    /// Show the event history (adds, updates, removals, focuses)
+    Log {
+        /// Only events for this project
+        #[arg(long)]
+        proj: Option<String>,
+
+        /// Only events for this agent (agent attr or "agent:" msg prefix)
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Only events after this time (e.g., 30m, 2h, 1d, or unix timestamp)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only events before this time (e.g., 30m, 2h, 1d, or unix timestamp)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Show only the last N matching events
+        #[arg(long, short = 'n')]
+        limit: Option<usize>,
+
+        /// Output as JSON lines
+        #[arg(long)]
+        json: bool,
     },
 
-    /// Clear all items
-    Clear,
+    /// Show response-time statistics from the event history
+    Stats {
+        /// Only consider events after this time (e.g., 1d, 1w, or unix timestamp)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print a compact summary for status bars
+    Status {
+        /// Preset (waybar, tmux, polybar) or template with {waiting}, {working},
+        /// {total}, {overdue} and {projects} placeholders
+        #[arg(long, default_value = statusbar::DEFAULT_FORMAT)]
+        format: String,
+    },
+
+    /// Stream inbox changes as newline-delimited JSON
+    Watch {
+        /// Also emit current items as "added" events on start
+        #[arg(long)]
+        initial: bool,
+    },
+
+    /// Run a hook command with a timeout (used internally by event hooks)
+    #[command(hide = true)]
+    HookExec {
+        /// Seconds before the command is killed
+        #[arg(long)]
+        timeout: u64,
+
+        /// Command and arguments
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+
+    /// Run a background daemon holding the inbox in memory.
+    /// Other tael commands use it automatically while it is running.
+    Daemon,
 
     /// Open interactive TUI
     #[command(alias = "ui")]
@@ -69,6 +242,10 @@ enum Commands {
         /// Group by attribute (e.g., proj, status)
         #[arg(long, value_delimiter = ',')]
         group_by: Vec<String>,
+
+        /// Show items from every session's inbox, grouped by session
+        #[arg(long)]
+        all_sessions: bool,
     },
 
     /// Launch TUI in a floating pane (Zellij)
@@ -88,9 +265,35 @@ enum Commands {
         /// Group by attribute (e.g., proj, status)
         #[arg(long, value_delimiter = ',')]
         group_by: Vec<String>,
+
+        /// Show items from every session's inbox, grouped by session
+        #[arg(long)]
+        all_sessions: bool,
     },
 }
 
+/// Fold inbox files left by older versions (one per tmux pane) into the
+/// per-session files. Best-effort: failures only print a warning.
+fn migrate_tmux_files(path: &std::path::Path) {
+    let dir = file::sessions_dir(path);
+    if let Err(e) = file::migrate_tmux_pane_files(dir, |pane| file::tmux_session(Some(pane))) {
+        eprintln!("warning: failed to migrate tmux inbox files: {}", e);
+    }
+}
+
+/// Ask a yes/no question on the terminal; anything but y/yes is a no
+fn confirm(prompt: &str) -> std::io::Result<bool> {
+    use std::io::Write;
+    print!("{} [y/N] ", prompt);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
 /// Extract value from JSON using @.field syntax
 fn extract_json_value(json: &serde_json::Value, expr: &str) -> Option<String> {
     // Simple path extraction: @.field or @.nested.field
@@ -141,19 +344,44 @@ fn main() {
 }
 
 fn run() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = Cli::parse();
-    let config = Config::new(cli.focus_cmd);
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let mut config = Config::new(cli.focus_cmd, cli.session_cmd);
+    config.sort = cli.sort;
+
+    // Agent hooks run this often; it needs no inbox, so don't resolve one
+    if let Some(Commands::HookExec { timeout, command }) = &cli.command {
+        hooks::run_with_timeout(command, std::time::Duration::from_secs(*timeout))?;
+        return Ok(());
+    }
 
-    let path = cli.file.unwrap_or_else(file::default_path);
+    // Passed with --file itself (not just set through TAEL_INBOX_FILE)
+    let explicit_file = matches.value_source("file") == Some(ValueSource::CommandLine);
+    let path = match cli.file {
+        Some(path) => path,
+        None => {
+            let path = file::default_path();
+            if std::env::var("TMUX").is_ok() {
+                migrate_tmux_files(&path);
+            }
+            path
+        }
+    };
 
     // Default to TUI if no subcommand
-    let command = cli.command.unwrap_or(Commands::Tui { group_by: vec![] });
+    let command = cli.command.unwrap_or(Commands::Tui {
+        group_by: vec![],
+        all_sessions: false,
+    });
 
     match command {
         Commands::Add {
             attrs,
             from_claude_code,
             status,
+            note,
+            key,
+            priority,
         } => {
             let status = match status.as_str() {
                 "wait" | "waiting" => Status::Waiting,
@@ -187,6 +415,10 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
                         item_attrs.insert("msg".to_string(), v);
                     }
                     if let Some(v) = extract_json_value(json, "@.notification_type") {
+                        // Permission prompts block the agent until answered
+                        if v == "permission_prompt" {
+                            item_attrs.insert("priority".to_string(), "high".to_string());
+                        }
                         item_attrs.insert("type".to_string(), v);
                     }
                 }
@@ -210,59 +442,482 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
                 item_attrs.insert(key.to_string(), resolved_value);
             }
 
-            let mut inbox = file::load(&path)?;
-            inbox.upsert(InboxItem::new(item_attrs.clone(), status));
-            file::save(&path, &inbox)?;
+            let is_note = note.is_some();
+            if let Some(note) = note {
+                if item_attrs.contains_key("pane") {
+                    return Err("--note items have no pane; drop the pane attr".into());
+                }
+                item_attrs.insert("msg".to_string(), note);
+                // Keyed by their own id, so notes never replace each other
+                item_attrs.insert("key".to_string(), "id".to_string());
+            }
+
+            if let Some(priority) = priority {
+                item_attrs.insert("priority".to_string(), priority.to_string());
+            }
+
+            if let Some(key) = key {
+                if !item_attrs.contains_key(&key) {
+                    return Err(format!("--key {} needs a {} attr", key, key).into());
+                }
+                item_attrs.insert("key".to_string(), key);
+            }
+
+            item_attrs
+                .entry("since".to_string())
+                .or_insert_with(|| time::now().to_string());
+
+            // Resolve the id up front so hooks and the output can use it:
+            // the id of the item being updated, else a fresh one
+            let mut store = Store::open(&path);
+            let mut item = InboxItem::new(item_attrs, status);
+            let inbox = store.load()?;
+            let existing = if is_note {
+                None
+            } else {
+                inbox.find(&item).map(|idx| &inbox.items[idx])
+            };
+            if item.id().is_none() {
+                let id = match existing.and_then(InboxItem::id) {
+                    Some(id) => id.to_string(),
+                    None => inbox.new_id(),
+                };
+                item.attrs.insert("id".to_string(), id);
+            }
+            // Agent updates leave a snooze alone until the status changes
+            if let Some(until) = existing
+                .filter(|e| e.status == item.status)
+                .and_then(|e| e.get("snooze_until"))
+            {
+                item.attrs
+                    .entry("snooze_until".to_string())
+                    .or_insert_with(|| until.to_string());
+            }
+            let replaced = store.add(item.clone())?;
+
+            let event = match replaced {
+                Some(_) => HookEvent::Update,
+                None => HookEvent::Add,
+            };
+            if let Err(e) = config.hooks.fire(event, &item) {
+                eprintln!("warning: {}", e);
+            }
+
+            if let Err(e) = notify::on_upsert(
+                &config.notify,
+                &path,
+                replaced.as_ref(),
+                &item,
+                || config.focused_pane(),
+                time::now(),
+            ) {
+                eprintln!("warning: {}", e);
+            }
+
+            // Agents add often, so this catches other overdue items too
+            if config.escalate.has(escalate::Action::Notify) {
+                let inbox = store.load()?;
+                if let Err(e) =
+                    escalate::check(&config.escalate, &config.notify, &path, &inbox, time::now())
+                {
+                    eprintln!("warning: {}", e);
+                }
+            }
 
             // Print confirmation
-            if let Some(pane) = item_attrs.get("pane") {
-                println!("Added item for pane {}", pane);
+            let id = item.id().unwrap_or_default();
+            if let Some(pane) = item.get("pane") {
+                println!("Added item {} for pane {}", id, pane);
             } else {
-                println!("Added item");
+                println!("Added item {}", id);
             }
         }
 
-        Commands::Remove { attrs } => {
+        Commands::Remove { id: Some(id), .. } => {
+            let mut store = Store::open(&path);
+            if let Some(removed) = store.remove_id(&id)? {
+                let label = format!("remove of item {}", id);
+                undo::record_removed(&path, &label, std::slice::from_ref(&removed));
+                if let Err(e) = config.hooks.fire(HookEvent::Remove, &removed) {
+                    eprintln!("warning: {}", e);
+                }
+                println!("Removed item {}", id);
+            } else {
+                println!("No item found with id {}", id);
+            }
+        }
+
+        Commands::Remove { attrs, .. } => {
             // Find pane attr
             let pane = attrs
                 .iter()
                 .find_map(|a| a.strip_prefix("pane=").and_then(|v| v.parse::<u32>().ok()))
-                .ok_or("pane attr required (use -a pane=N)")?;
+                .ok_or("pane attr or id required (use -a pane=N or --id ID)")?;
 
-            let mut inbox = file::load(&path)?;
-            if inbox.remove(pane) {
-                file::save(&path, &inbox)?;
-                println!("Removed item for pane {}", pane);
-            } else {
+            let mut store = Store::open(&path);
+            let removed = store.remove(pane)?;
+            if removed.is_empty() {
                 println!("No item found for pane {}", pane);
+            } else {
+                undo::record_removed(&path, &format!("remove of pane {}", pane), &removed);
+                for item in &removed {
+                    if let Err(e) = config.hooks.fire(HookEvent::Remove, item) {
+                        eprintln!("warning: {}", e);
+                    }
+                }
+                match removed.len() {
+                    1 => println!("Removed item for pane {}", pane),
+                    n => println!("Removed {} items for pane {}", n, pane),
+                }
             }
         }
 
-        Commands::List { json, group_by } => {
-            use std::io::IsTerminal;
-            let inbox = file::load(&path)?;
+        Commands::Snooze { id, duration, wake } => {
+            let now = time::now();
+            let until = match duration {
+                Some(d) if !wake => Some(
+                    time::parse_until(&d, now)
+                        .ok_or_else(|| format!("invalid duration '{}'", d))?,
+                ),
+                _ => None,
+            };
+            let mut store = Store::open(&path);
+            let before = store.load()?;
+            let mut item = before
+                .by_id(&id)
+                .cloned()
+                .ok_or_else(|| format!("no item with id {}", id))?;
+            match until {
+                Some(until) => item
+                    .attrs
+                    .insert("snooze_until".to_string(), until.to_string()),
+                None => item.attrs.remove("snooze_until"),
+            };
+            let replaced = store.add(item.clone())?;
+            let action = if until.is_some() { "snooze" } else { "wake" };
+            undo::record(
+                &path,
+                &format!("{} of item {}", action, id),
+                undo::ItemChange::added(replaced, item.clone()),
+            );
+            if let Err(e) = config.hooks.fire(HookEvent::Update, &item) {
+                eprintln!("warning: {}", e);
+            }
+            match until {
+                Some(until) => println!(
+                    "Snoozed item {} for {}",
+                    id,
+                    time::format_duration(until.saturating_sub(now))
+                ),
+                None => println!("Woke item {}", id),
+            }
+        }
+
+        Commands::Show { id, json } => {
+            let inbox = Store::open(&path).load()?;
+            let item = inbox
+                .by_id(&id)
+                .ok_or_else(|| format!("no item with id {}", id))?;
             if json {
-                println!("{}", serde_json::to_string_pretty(&inbox)?);
+                println!("{}", serde_json::to_string_pretty(item)?);
+            } else {
+                print!("{}", render::details(item, time::now()));
+            }
+        }
+
+        Commands::List {
+            json,
+            format,
+            mut group_by,
+            all_sessions,
+            snoozed,
+        } => {
+            use std::io::IsTerminal;
+            let mut inbox = if all_sessions {
+                if group_by.is_empty() {
+                    group_by.push("session".to_string());
+                }
+                file::load_all(file::sessions_dir(&path))?
+            } else {
+                Store::open(&path).load()?
+            };
+            let now = time::now();
+            let first_snoozed = tael::tui::arrange(&mut inbox, &config.sort, &group_by, now);
+            if !snoozed {
+                inbox.items.truncate(first_snoozed);
+            }
+            let format = format.or_else(|| json.then(|| "json".to_string()));
+            if let Some(format) = format {
+                print!("{}", export::export(&inbox, &format)?);
             } else {
                 let width = ratatui::crossterm::terminal::size()
                     .map(|(w, _)| w as usize)
                     .unwrap_or(80);
                 let is_tty = std::io::stdout().is_terminal();
+                let hidden = Inbox {
+                    items: inbox.items.split_off(first_snoozed.min(inbox.items.len())),
+                };
                 print!(
                     "{}",
                     tael::tui::render_list(&inbox, width, is_tty, &group_by)
                 );
+                if !hidden.is_empty() {
+                    print!("{}", tael::tui::render_snoozed(&hidden, width, is_tty, now));
+                }
+            }
+        }
+
+        Commands::Clear { yes } => {
+            use std::io::IsTerminal;
+            let mut store = Store::open(&path);
+            let before = store.load()?;
+            let count = before.items.iter().filter(|i| !i.is_pinned()).count();
+            if count == 0 {
+                println!("Nothing to clear");
+                return Ok(());
+            }
+            if !yes
+                && std::io::stdout().is_terminal()
+                && !confirm(&format!("Clear {} item(s)?", count))?
+            {
+                println!("Aborted");
+                return Ok(());
+            }
+            let removed = store.clear()?;
+            let label = format!("clear of {} item(s)", removed.len());
+            undo::record_removed(&path, &label, &removed);
+            for removed in removed {
+                if let Err(e) = config.hooks.fire(HookEvent::Remove, &removed) {
+                    eprintln!("warning: {}", e);
+                }
+            }
+            let kept = before.items.len() - count;
+            if kept > 0 {
+                println!("Cleared inbox (kept {} pinned)", kept);
+            } else {
+                println!("Cleared inbox");
             }
         }
 
-        Commands::Clear => {
-            let inbox = Inbox::new();
-            file::save(&path, &inbox)?;
-            println!("Cleared inbox");
+        Commands::Gc { older_than } => {
+            let ttl = time::parse_duration(&older_than)
+                .ok_or_else(|| format!("invalid duration '{}'", older_than))?;
+            let mut store = Store::open(&path);
+            let before = store.load()?;
+            let ids = before.expired(time::now().saturating_sub(ttl));
+            let removed = if ids.is_empty() {
+                Vec::new()
+            } else {
+                store.remove_many(&ids)?
+            };
+            if removed.is_empty() {
+                println!("Nothing older than {}", older_than);
+                return Ok(());
+            }
+            let label = format!("gc of {} item(s)", removed.len());
+            undo::record_removed(&path, &label, &removed);
+            for item in &removed {
+                if let Err(e) = config.hooks.fire(HookEvent::Remove, item) {
+                    eprintln!("warning: {}", e);
+                }
+            }
+            println!(
+                "Removed {} item(s) older than {}",
+                removed.len(),
+                older_than
+            );
         }
 
-        Commands::Tui { group_by } => {
-            tael::tui::run_interactive(&config, &group_by)?;
+        Commands::Undo | Commands::Redo => {
+            let is_undo = matches!(command, Commands::Undo);
+            let step = if is_undo {
+                undo::undo(&path)?
+            } else {
+                undo::redo(&path)?
+            };
+            match step {
+                Some(step) => {
+                    for change in &step.changes {
+                        let (event, item) = HookEvent::for_change(change);
+                        if let Err(e) = config.hooks.fire(event, item) {
+                            eprintln!("warning: {}", e);
+                        }
+                    }
+                    let verb = if is_undo { "Undid" } else { "Redid" };
+                    println!(
+                        "{} {}{}",
+                        verb,
+                        step.label,
+                        undo::skipped_note(step.skipped)
+                    );
+                }
+                None if is_undo => println!("Nothing to undo"),
+                None => println!("Nothing to redo"),
+            }
+        }
+
+        Commands::Import { input, on_conflict } => {
+            let strategy: import::Conflict = on_conflict.parse()?;
+            let content = match input {
+                Some(ref f) if f.as_os_str() != "-" => std::fs::read_to_string(f)?,
+                _ => {
+                    use std::io::Read;
+                    let mut input = String::new();
+                    std::io::stdin().read_to_string(&mut input)?;
+                    input
+                }
+            };
+            let items = import::parse_items(&content)?;
+
+            let mut store = Store::open(&path);
+            let before = store.load()?;
+            let plan = import::plan(&before, items, strategy);
+            if !plan.upserts.is_empty() {
+                let label = format!("import of {} item(s)", plan.upserts.len());
+                // Put matches by id, so the replaced versions are the ones the
+                // plan was made from
+                let changes = plan
+                    .upserts
+                    .iter()
+                    .map(|item| undo::ItemChange {
+                        before: item.id().and_then(|id| before.by_id(id)).cloned(),
+                        after: Some(item.clone()),
+                    })
+                    .collect();
+                store.put_many(plan.upserts)?;
+                undo::record(&path, &label, changes);
+            }
+            println!(
+                "Imported {} new, {} replaced, {} skipped",
+                plan.added, plan.replaced, plan.skipped
+            );
+        }
+
+        Commands::Log {
+            proj,
+            agent,
+            since,
+            until,
+            limit,
+            json,
+        } => {
+            let now = time::now();
+            let parse_bound = |s: Option<String>| -> Result<Option<u64>, String> {
+                s.map(|s| {
+                    time::parse_time(&s, now)
+                        .ok_or_else(|| format!("invalid time '{}': use e.g. 30m, 2h, 1d", s))
+                })
+                .transpose()
+            };
+            let filter = history::Filter {
+                proj,
+                agent,
+                since: parse_bound(since)?,
+                until: parse_bound(until)?,
+            };
+
+            let events = history::load(&history::path_for(&path))?;
+            let mut matched: Vec<_> = events.iter().filter(|e| filter.matches(e)).collect();
+            if let Some(n) = limit {
+                matched.drain(..matched.len().saturating_sub(n));
+            }
+
+            for event in matched {
+                if json {
+                    println!("{}", serde_json::to_string(event)?);
+                } else {
+                    println!(
+                        "{}  {:<6}  {:<12}  {}",
+                        time::format_timestamp(event.ts),
+                        event.event.as_str(),
+                        event.item.proj().unwrap_or("-"),
+                        event.item.msg()
+                    );
+                }
+            }
+        }
+
+        Commands::Stats { since, json } => {
+            let now = time::now();
+            let since = since
+                .map(|s| {
+                    time::parse_time(&s, now)
+                        .ok_or_else(|| format!("invalid time '{}': use e.g. 1d, 1w", s))
+                })
+                .transpose()?;
+
+            let events = history::load(&history::path_for(&path))?;
+            let inbox = Store::open(&path).load()?;
+            let stats = stats::compute(&events, &inbox, now, since.unwrap_or(0));
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            } else {
+                print!("{}", stats::render_text(&stats));
+            }
+        }
+
+        Commands::Status { format } => {
+            let mut inbox = Store::open(&path).load()?;
+            // Status bars poll this, so it doubles as the escalation check
+            let now = time::now();
+            inbox.items.retain(|i| !i.is_snoozed(now));
+            if let Err(e) = escalate::check(&config.escalate, &config.notify, &path, &inbox, now) {
+                eprintln!("warning: {}", e);
+            }
+            let overdue = if config.escalate.has(escalate::Action::Flash) {
+                escalate::overdue(&config.escalate, &inbox, now).len()
+            } else {
+                0
+            };
+            println!("{}", statusbar::render_flashing(&inbox, &format, overdue));
+        }
+
+        Commands::Watch { initial } => {
+            use std::io::Write;
+
+            let snapshots: Box<dyn Iterator<Item = std::io::Result<Inbox>>> =
+                match Store::open(&path) {
+                    Store::Daemon(client) => Box::new(client.subscribe()?),
+                    Store::File(path) => Box::new(watch::poll_file(&path)),
+                };
+
+            let mut previous: Option<Inbox> = None;
+            let mut stdout = std::io::stdout().lock();
+            for snapshot in snapshots {
+                let snapshot = snapshot?;
+                let changes = match previous {
+                    Some(ref prev) => watch::diff(prev, &snapshot),
+                    None if initial => watch::diff(&Inbox::new(), &snapshot),
+                    None => Vec::new(),
+                };
+                for change in changes {
+                    writeln!(stdout, "{}", serde_json::to_string(&change)?)?;
+                }
+                stdout.flush()?;
+                previous = Some(snapshot);
+            }
+        }
+
+        Commands::HookExec { .. } => unreachable!("handled before resolving the inbox"),
+
+        Commands::Daemon => {
+            let server = daemon::Server::bind(&path)?;
+            server.cleanup_on_signal()?;
+            server.escalate_every(
+                daemon::ESCALATE_INTERVAL,
+                config.escalate.clone(),
+                config.notify.clone(),
+            );
+            eprintln!("tael daemon listening on {}", server.socket().display());
+            server.run()?;
+        }
+
+        Commands::Tui {
+            group_by,
+            all_sessions,
+        } => {
+            tael::tui::run_interactive(&config, &path, &group_by, all_sessions)?;
         }
 
         Commands::Float {
@@ -270,6 +925,7 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
             width,
             height,
             group_by,
+            all_sessions,
         } => {
             // Only works in Zellij
             if std::env::var("ZELLIJ").is_err() {
@@ -292,6 +948,19 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
                 tael_args.push("--group-by".to_string());
                 tael_args.push(group_by.join(","));
             }
+            if all_sessions {
+                tael_args.push("--all-sessions".to_string());
+            }
+            tael_args.push("--sort".to_string());
+            tael_args.push(config.sort.to_string());
+            if explicit_file {
+                tael_args.push("--file".to_string());
+                // The floating pane may start in another directory
+                let path = path
+                    .canonicalize()
+                    .or_else(|_| std::path::absolute(&path))?;
+                tael_args.push(path.display().to_string());
+            }
 
             // Get path to current executable
             let exe = std::env::current_exe()?;
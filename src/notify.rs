@@ -0,0 +1,323 @@
+//! Desktop notifications when an item starts waiting
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::{InboxItem, Status};
+
+/// Notification rule: all `key=value` conditions must match.
+/// Rules prefixed with `!` suppress instead of allow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub negate: bool,
+    pub conditions: Vec<(String, String)>,
+}
+
+impl Rule {
+    /// Parse "proj=tael,type=permission_prompt" or "!type=idle_prompt"
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        let (negate, s) = match s.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let conditions = s
+            .split(',')
+            .map(|cond| {
+                cond.trim()
+                    .split_once('=')
+                    .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+                    .ok_or_else(|| format!("invalid notify rule '{}': expected key=value", cond))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { negate, conditions })
+    }
+
+    fn matches(&self, item: &InboxItem) -> bool {
+        self.conditions
+            .iter()
+            .all(|(k, v)| item.get(k) == Some(v.as_str()))
+    }
+}
+
+/// Notification settings
+#[derive(Debug, Clone)]
+pub struct NotifyConfig {
+    /// Send notifications at all
+    pub enabled: bool,
+    /// Allow/suppress rules (no allow rules means allow everything)
+    pub rules: Vec<Rule>,
+    /// Minimum seconds between notifications for the same item
+    pub min_interval: u64,
+    /// Skip notifying when the item's pane is already focused
+    pub skip_focused: bool,
+    /// Custom notifier command ({title}, {body} and attr placeholders)
+    pub command: Option<String>,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rules: Vec::new(),
+            min_interval: 30,
+            skip_focused: true,
+            command: None,
+        }
+    }
+}
+
+impl NotifyConfig {
+    /// Load from TAEL_NOTIFY* environment variables (invalid rules are ignored)
+    pub fn from_env() -> Self {
+        let flag = |name: &str| {
+            env::var(name)
+                .ok()
+                .map(|v| matches!(v.as_str(), "1" | "true" | "yes" | "on"))
+        };
+        let defaults = Self::default();
+        Self {
+            enabled: flag("TAEL_NOTIFY").unwrap_or(defaults.enabled),
+            rules: env::var("TAEL_NOTIFY_RULES")
+                .map(|v| {
+                    v.split(';')
+                        .filter(|r| !r.trim().is_empty())
+                        .filter_map(|r| Rule::parse(r).ok())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            min_interval: env::var("TAEL_NOTIFY_INTERVAL")
+                .ok()
+                .and_then(|v| crate::time::parse_duration(&v))
+                .unwrap_or(defaults.min_interval),
+            skip_focused: flag("TAEL_NOTIFY_SKIP_FOCUSED").unwrap_or(defaults.skip_focused),
+            command: env::var("TAEL_NOTIFY_CMD").ok().filter(|c| !c.is_empty()),
+        }
+    }
+
+    /// Check the item against the rules
+    pub fn allows(&self, item: &InboxItem) -> bool {
+        if self.rules.iter().any(|r| r.negate && r.matches(item)) {
+            return false;
+        }
+        let mut allow = self.rules.iter().filter(|r| !r.negate).peekable();
+        allow.peek().is_none() || allow.any(|r| r.matches(item))
+    }
+}
+
+/// Whether an upsert moved an item into Waiting
+pub fn started_waiting(previous: Option<&InboxItem>, item: &InboxItem) -> bool {
+    item.status == Status::Waiting && previous.is_none_or(|p| p.status != Status::Waiting)
+}
+
+/// Rate-limit state file for an inbox file (`foo.md` -> `foo.notify.json`)
+pub fn state_path(inbox_path: &Path) -> PathBuf {
+    let stem = inbox_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("inbox");
+    inbox_path.with_file_name(format!("{}.notify.json", stem))
+}
+
+/// Notify about an upserted item if it just started waiting and the
+/// rules, focus state and rate limit allow it. Returns whether one was sent.
+pub fn on_upsert(
+    config: &NotifyConfig,
+    inbox_path: &Path,
+    previous: Option<&InboxItem>,
+    item: &InboxItem,
+    focused_pane: impl FnOnce() -> Option<u32>,
+    now: u64,
+) -> Result<bool, String> {
+    if !config.enabled || !started_waiting(previous, item) || !config.allows(item) {
+        return Ok(false);
+    }
+    if config.skip_focused && item.pane_id().is_some() && item.pane_id() == focused_pane() {
+        return Ok(false);
+    }
+
+    // Rate limit per item
+    let state_path = state_path(inbox_path);
+    let mut last_sent: HashMap<String, u64> = fs::read_to_string(&state_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+    let key = item
+        .get("pane")
+        .map(|p| format!("pane:{}", p))
+        .unwrap_or_else(|| format!("msg:{}", item.msg()));
+    if last_sent
+        .get(&key)
+        .is_some_and(|&ts| now.saturating_sub(ts) < config.min_interval)
+    {
+        return Ok(false);
+    }
+
+    send(config, item)?;
+
+    last_sent.insert(key, now);
+    last_sent.retain(|_, ts| now.saturating_sub(*ts) < config.min_interval.max(60) * 10);
+    if let Ok(json) = serde_json::to_string(&last_sent) {
+        let _ = fs::write(&state_path, json);
+    }
+    Ok(true)
+}
+
+/// Send a notification for an item: custom command, else D-Bus, else
+/// notify-send. The notifier runs in the background so callers (e.g. `tael
+/// add` in an agent hook) never wait for it.
+pub fn send(config: &NotifyConfig, item: &InboxItem) -> Result<(), String> {
+    let title = match item.proj() {
+        Some(proj) => format!("tael: {}", proj),
+        None => "tael".to_string(),
+    };
+    let body = item.msg();
+
+    if let Some(ref template) = config.command {
+        let parts = shell_words::split(template)
+            .map_err(|e| format!("Failed to parse notify command: {}", e))?;
+        let parts: Vec<String> = parts
+            .iter()
+            .map(|p| expand(p, item, &title, body))
+            .collect();
+        return spawn(&parts);
+    }
+
+    // One shell, so falling back to notify-send doesn't need us to wait
+    let script = "gdbus call --session --dest org.freedesktop.Notifications \
+        --object-path /org/freedesktop/Notifications \
+        --method org.freedesktop.Notifications.Notify \
+        tael 0 '' \"$1\" \"$2\" '[]' '{}' -1 \
+        || notify-send --app-name=tael \"$1\" \"$2\"";
+    spawn(&["sh", "-c", script, "sh", &title, body])
+}
+
+/// Fill {title} and {body} plus the placeholders of `InboxItem::fill_template`
+/// in one pass, so values containing braces are left as they are
+fn expand(template: &str, item: &InboxItem, title: &str, body: &str) -> String {
+    let mut item = item.clone();
+    item.attrs.insert("title".to_string(), title.to_string());
+    item.attrs.insert("body".to_string(), body.to_string());
+    item.fill_template(template)
+}
+
+/// Start a notifier without waiting for it to finish
+fn spawn<S: AsRef<std::ffi::OsStr>>(parts: &[S]) -> Result<(), String> {
+    let (cmd, args) = parts.split_first().ok_or("Empty notify command")?;
+    let mut child = Command::new(cmd)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to execute notify command: {}", e))?;
+    // Reap it so long-running callers (TUI, daemon) don't collect zombies;
+    // short-lived ones exit without waiting and leave it running
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_item, read_log};
+    use tempfile::TempDir;
+
+    fn fake_notifier(dir: &TempDir) -> (NotifyConfig, PathBuf) {
+        let log = dir.path().join("notifications.log");
+        let config = NotifyConfig {
+            enabled: true,
+            command: Some(format!(
+                "sh -c 'echo \"$0|$1\" >> {}' {{title}} {{msg}}",
+                log.display()
+            )),
+            ..Default::default()
+        };
+        (config, log)
+    }
+
+    #[test]
+    fn parse_rules() {
+        let rule = Rule::parse("proj=tael, type=permission_prompt").unwrap();
+        assert!(!rule.negate);
+        assert_eq!(rule.conditions.len(), 2);
+        assert!(Rule::parse("!type=idle_prompt").unwrap().negate);
+        assert!(Rule::parse("proj").is_err());
+    }
+
+    #[test]
+    fn rules_allow_and_suppress() {
+        let tael = make_item("hi", 1, "tael", None, Status::Waiting);
+        let k3s = make_item("hi", 2, "k3s", None, Status::Waiting);
+
+        let mut config = NotifyConfig::default();
+        assert!(config.allows(&tael));
+
+        config.rules = vec![Rule::parse("proj=tael").unwrap()];
+        assert!(config.allows(&tael));
+        assert!(!config.allows(&k3s));
+
+        config.rules = vec![Rule::parse("!proj=tael").unwrap()];
+        assert!(!config.allows(&tael));
+        assert!(config.allows(&k3s));
+    }
+
+    #[test]
+    fn only_transitions_into_waiting() {
+        let waiting = make_item("hi", 1, "tael", None, Status::Waiting);
+        let working = make_item("hi", 1, "tael", None, Status::Working);
+        assert!(started_waiting(None, &waiting));
+        assert!(started_waiting(Some(&working), &waiting));
+        assert!(!started_waiting(Some(&waiting), &waiting));
+        assert!(!started_waiting(None, &working));
+    }
+
+    #[test]
+    fn sends_via_fake_notifier_with_rate_limit() {
+        let dir = TempDir::new().unwrap();
+        let inbox_path = dir.path().join("test.md");
+        let (config, log) = fake_notifier(&dir);
+        let item = make_item("claude: Approve?", 1, "tael", None, Status::Waiting);
+
+        assert!(on_upsert(&config, &inbox_path, None, &item, || None, 1000).unwrap());
+        assert_eq!(read_log(&log, 1), "tael: tael|claude: Approve?\n");
+
+        // Within the interval: suppressed
+        assert!(!on_upsert(&config, &inbox_path, None, &item, || None, 1010).unwrap());
+        // After the interval: sent again
+        assert!(on_upsert(&config, &inbox_path, None, &item, || None, 1100).unwrap());
+        assert_eq!(read_log(&log, 2).lines().count(), 2);
+    }
+
+    #[test]
+    fn skips_focused_pane() {
+        let dir = TempDir::new().unwrap();
+        let inbox_path = dir.path().join("test.md");
+        let (mut config, log) = fake_notifier(&dir);
+        let item = make_item("hi", 7, "tael", None, Status::Waiting);
+
+        assert!(!on_upsert(&config, &inbox_path, None, &item, || Some(7), 0).unwrap());
+        assert!(!log.exists());
+
+        config.skip_focused = false;
+        assert!(on_upsert(&config, &inbox_path, None, &item, || Some(7), 0).unwrap());
+    }
+
+    #[test]
+    fn expand_uses_the_item_template_syntax() {
+        let mut item = make_item("use {proj}", 42, "tael", None, Status::Waiting);
+        item.attrs.insert("note".to_string(), "{body}".to_string());
+        assert_eq!(
+            expand(
+                "{title}|{body}|{note}|{status}|{pane_id}",
+                &item,
+                "tael: x",
+                item.msg()
+            ),
+            "tael: x|use {proj}|{body}|waiting|42"
+        );
+    }
+}
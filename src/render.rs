@@ -1,6 +1,31 @@
 //! Markdown rendering for inbox files
 
-use crate::{Inbox, Status};
+use crate::{time, Inbox, InboxItem, Status};
+
+/// Item details as aligned "key  value" lines: status (with how long, if
+/// known), then msg, then the other attrs alphabetically
+pub fn details(item: &InboxItem, now: u64) -> String {
+    let mut status = item.status.as_str().to_string();
+    if let Some(since) = item.since() {
+        status = format!(
+            "{} for {}",
+            status,
+            time::format_duration(now.saturating_sub(since))
+        );
+    }
+    let mut lines = vec![("status", status), ("msg", item.msg().to_string())];
+    let mut keys: Vec<&String> = item.attrs.keys().filter(|k| *k != "msg").collect();
+    keys.sort();
+    lines.extend(
+        keys.into_iter()
+            .map(|k| (k.as_str(), item.attrs[k].clone())),
+    );
+    let width = lines.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+    lines
+        .into_iter()
+        .map(|(k, v)| format!("{:width$}  {}\n", k, v, width = width))
+        .collect()
+}
 
 /// Render inbox to markdown with flat attrs
 pub fn render(inbox: &Inbox) -> String {
@@ -11,6 +36,8 @@ pub fn render(inbox: &Inbox) -> String {
     let mut output = String::new();
     let mut current_status: Option<Status> = None;
 
+    // Items stay in inbox order (display order is up to the viewer), so a
+    // status may get more than one section
     for item in &inbox.items {
         // Section header on status change
         if current_status != Some(item.status) {
@@ -130,7 +157,7 @@ mod tests {
 
     #[test]
     fn render_roundtrip() {
-        let inbox = Inbox {
+        let mut inbox = Inbox {
             items: vec![
                 make_item(
                     "claude-code: Auth question",
@@ -142,6 +169,9 @@ mod tests {
                 make_item("indexer: Processing", 5, "crucible", None, Status::Working),
             ],
         };
+        inbox.items[0]
+            .attrs
+            .insert("snooze_until".to_string(), "1800000000".to_string());
 
         let markdown = render(&inbox);
         let parsed = crate::parse::parse(&markdown);
@@ -151,6 +181,7 @@ mod tests {
             assert_eq!(orig.pane_id(), parsed.pane_id());
             assert_eq!(orig.msg(), parsed.msg());
             assert_eq!(orig.status, parsed.status);
+            assert_eq!(orig.snooze_until(), parsed.snooze_until());
         }
     }
 
@@ -199,9 +230,27 @@ mod tests {
         let output = render(&inbox);
         assert!(output.contains("## Waiting"));
         assert!(output.contains("## Working"));
-        // Verify sections appear in order (Waiting before Working based on sort)
+        // Verify sections appear in order (Waiting before Working)
         let waiting_pos = output.find("## Waiting").unwrap();
         let working_pos = output.find("## Working").unwrap();
         assert!(waiting_pos < working_pos);
     }
+
+    #[test]
+    fn render_keeps_inbox_order() {
+        let inbox = Inbox {
+            items: vec![
+                make_item("first", 1, "tael", None, Status::Working),
+                make_item("second", 2, "tael", None, Status::Waiting),
+                make_item("third", 3, "tael", None, Status::Working),
+            ],
+        };
+
+        let output = render(&inbox);
+        assert_eq!(output.matches("## Working").count(), 2);
+        let parsed = crate::parse::parse(&output);
+        let msgs: Vec<_> = parsed.items.iter().map(|i| i.msg()).collect();
+        assert_eq!(msgs, vec!["first", "second", "third"]);
+        assert_eq!(parsed.items[1].status, Status::Waiting);
+    }
 }
@@ -0,0 +1,240 @@
+//! Display sort orders, e.g. `--sort proj,-age`
+//!
+//! A sort is a comma-separated list of keys, each optionally prefixed with
+//! `-` to reverse it. Sorting is stable, so ties keep inbox order.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{InboxItem, Status};
+
+/// Sort order used when none is configured
+pub const DEFAULT: &str = "status,priority,proj";
+
+/// What a sort key compares
+#[derive(Debug, Clone, PartialEq)]
+pub enum Field {
+    /// Waiting before Working (`-status` for the reverse)
+    Status,
+    /// Time since the item entered its status, newest first
+    Age,
+    /// Most urgent first (see the "priority" attr)
+    Priority,
+    /// Any attr; numbers compare numerically, missing values go last
+    Attr(String),
+}
+
+/// One key of a sort order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Key {
+    pub field: Field,
+    pub descending: bool,
+}
+
+/// A sort order: keys compared in turn until one differs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sort(pub Vec<Key>);
+
+impl Default for Sort {
+    fn default() -> Self {
+        DEFAULT.parse().expect("valid default sort")
+    }
+}
+
+impl FromStr for Sort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let keys = s
+            .split(',')
+            .map(|key| {
+                let key = key.trim();
+                let (descending, name) = match key.strip_prefix('-') {
+                    Some(name) => (true, name),
+                    None => (false, key),
+                };
+                let field = match name {
+                    "" => return Err(format!("invalid sort '{}': empty key", s)),
+                    "status" => Field::Status,
+                    "age" => Field::Age,
+                    "priority" => Field::Priority,
+                    attr => Field::Attr(attr.to_string()),
+                };
+                Ok(Key { field, descending })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self(keys))
+    }
+}
+
+impl fmt::Display for Sort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys: Vec<String> = self
+            .0
+            .iter()
+            .map(|key| {
+                let name = match &key.field {
+                    Field::Status => "status",
+                    Field::Age => "age",
+                    Field::Priority => "priority",
+                    Field::Attr(attr) => attr,
+                };
+                format!("{}{}", if key.descending { "-" } else { "" }, name)
+            })
+            .collect();
+        write!(f, "{}", keys.join(","))
+    }
+}
+
+impl Sort {
+    /// Stable-sort items; `now` (unix seconds) is used for ages
+    pub fn apply(&self, items: &mut [InboxItem], now: u64) {
+        items.sort_by(|a, b| self.compare(a, b, now));
+    }
+
+    /// Compare two items by each key in turn
+    pub fn compare(&self, a: &InboxItem, b: &InboxItem, now: u64) -> Ordering {
+        self.0
+            .iter()
+            .map(|key| key.compare(a, b, now))
+            .find(|o| o.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl Key {
+    fn compare(&self, a: &InboxItem, b: &InboxItem, now: u64) -> Ordering {
+        let order = match &self.field {
+            Field::Status => status_rank(a.status).cmp(&status_rank(b.status)),
+            Field::Age => age(a, now).cmp(&age(b, now)),
+            Field::Priority => a.priority().cmp(&b.priority()),
+            Field::Attr(attr) => {
+                // Missing values go last either way
+                return match (a.get(attr), b.get(attr)) {
+                    (Some(x), Some(y)) => self.direct(compare_values(x, y)),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                };
+            }
+        };
+        self.direct(order)
+    }
+
+    fn direct(&self, order: Ordering) -> Ordering {
+        if self.descending {
+            order.reverse()
+        } else {
+            order
+        }
+    }
+}
+
+fn status_rank(status: Status) -> u8 {
+    match status {
+        Status::Waiting => 0,
+        Status::Working => 1,
+    }
+}
+
+/// Seconds in the current status (0 if unknown)
+fn age(item: &InboxItem, now: u64) -> u64 {
+    item.since().map_or(0, |since| now.saturating_sub(since))
+}
+
+/// Numbers numerically, anything else as text
+fn compare_values(a: &str, b: &str) -> Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(x), Ok(y)) => x.total_cmp(&y),
+        _ => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::make_item;
+
+    fn item(msg: &str, proj: &str, status: Status, since: u64) -> InboxItem {
+        let mut item = make_item(msg, 1, proj, None, status);
+        item.attrs.insert("since".to_string(), since.to_string());
+        item
+    }
+
+    fn msgs(items: &[InboxItem]) -> Vec<&str> {
+        items.iter().map(|i| i.msg()).collect()
+    }
+
+    #[test]
+    fn parse_and_display_round_trip() {
+        let sort: Sort = "proj,-age".parse().unwrap();
+        assert_eq!(
+            sort.0,
+            vec![
+                Key {
+                    field: Field::Attr("proj".to_string()),
+                    descending: false
+                },
+                Key {
+                    field: Field::Age,
+                    descending: true
+                },
+            ]
+        );
+        assert_eq!(sort.to_string(), "proj,-age");
+        assert!("proj,".parse::<Sort>().is_err());
+        assert_eq!(Sort::default().to_string(), DEFAULT);
+    }
+
+    #[test]
+    fn sorts_by_keys_in_turn_and_keeps_ties_stable() {
+        let mut items = vec![
+            item("b-new", "b", Status::Waiting, 90),
+            item("a-work", "a", Status::Working, 10),
+            item("b-old", "b", Status::Waiting, 10),
+            item("a-new", "a", Status::Waiting, 90),
+            item("a-new-2", "a", Status::Waiting, 90),
+        ];
+
+        let mut by_proj = items.clone();
+        "proj,-age"
+            .parse::<Sort>()
+            .unwrap()
+            .apply(&mut by_proj, 100);
+        assert_eq!(
+            msgs(&by_proj),
+            vec!["a-work", "a-new", "a-new-2", "b-old", "b-new"]
+        );
+
+        Sort::default().apply(&mut items, 100);
+        assert_eq!(
+            msgs(&items),
+            vec!["a-new", "a-new-2", "b-new", "b-old", "a-work"]
+        );
+
+        "-status".parse::<Sort>().unwrap().apply(&mut items, 100);
+        assert_eq!(msgs(&items)[0], "a-work");
+    }
+
+    #[test]
+    fn attrs_compare_numbers_and_put_missing_last() {
+        let mut items = vec![
+            make_item("none", 1, "x", None, Status::Waiting),
+            make_item("ten", 10, "x", None, Status::Waiting),
+            make_item("nine", 9, "x", None, Status::Waiting),
+        ];
+        items[0].attrs.remove("pane");
+        "-pane".parse::<Sort>().unwrap().apply(&mut items, 0);
+        assert_eq!(msgs(&items), vec!["ten", "nine", "none"]);
+
+        items[0]
+            .attrs
+            .insert("priority".to_string(), "low".to_string());
+        items[2]
+            .attrs
+            .insert("priority".to_string(), "urgent".to_string());
+        "priority".parse::<Sort>().unwrap().apply(&mut items, 0);
+        assert_eq!(msgs(&items), vec!["none", "nine", "ten"]);
+    }
+}
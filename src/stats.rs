@@ -0,0 +1,346 @@
+//! Response-time statistics computed from the event history
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use crate::history::{Event, EventKind};
+use crate::{Inbox, Status};
+
+/// Counts and wait times for one project or agent
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GroupStats {
+    pub name: String,
+    /// Items added
+    pub added: usize,
+    /// Completed waits (Waiting -> Working or removed)
+    pub waits: usize,
+    /// Median seconds spent in Waiting
+    pub median_wait: Option<u64>,
+    /// 90th percentile seconds spent in Waiting
+    pub p90_wait: Option<u64>,
+    /// Total seconds spent in Waiting
+    pub total_wait: u64,
+}
+
+/// Currently waiting item that has waited the longest
+#[derive(Debug, Clone, Serialize)]
+pub struct LongestWaiting {
+    pub msg: String,
+    pub proj: Option<String>,
+    pub pane: Option<u32>,
+    /// Seconds waiting so far
+    pub waiting: u64,
+}
+
+/// Aggregated statistics
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Stats {
+    pub events: usize,
+    pub median_wait: Option<u64>,
+    pub p90_wait: Option<u64>,
+    pub by_proj: Vec<GroupStats>,
+    pub by_agent: Vec<GroupStats>,
+    /// (hour of day UTC, number of items that started waiting), busiest first
+    pub busiest_hours: Vec<(u8, usize)>,
+    pub longest_waiting: Option<LongestWaiting>,
+}
+
+/// A closed Waiting span attributed to an item's project and agent
+struct Wait {
+    proj: String,
+    agent: String,
+    secs: u64,
+}
+
+/// Compute statistics from history events (in file order) and the current
+/// inbox, counting only what happened at or after `since`. Earlier events are
+/// still replayed so waits that span the cutoff are measured in full.
+pub fn compute(events: &[Event], inbox: &Inbox, now: u64, since: u64) -> Stats {
+    // Open waits keyed by item id (pane or msg for events from before items
+    // had ids), attributed to the event that opened them
+    let mut open: HashMap<String, (u64, String, String)> = HashMap::new();
+    let mut waits = Vec::new();
+    let mut added_by_proj: HashMap<String, usize> = HashMap::new();
+    let mut added_by_agent: HashMap<String, usize> = HashMap::new();
+    let mut hours: BTreeMap<u8, usize> = BTreeMap::new();
+    let mut counted = 0;
+
+    for event in events {
+        let recent = event.ts >= since;
+        if recent {
+            counted += 1;
+        }
+        let item = &event.item;
+        let key = match (item.id(), item.get("pane")) {
+            (Some(id), _) => format!("id:{}", id),
+            (None, Some(pane)) => format!("pane:{}", pane),
+            (None, None) => format!("msg:{}", item.msg()),
+        };
+        let proj = item.proj().unwrap_or("(no project)").to_string();
+        let agent = item.agent().unwrap_or("(unknown)").to_string();
+
+        if event.event == EventKind::Add && recent {
+            *added_by_proj.entry(proj.clone()).or_default() += 1;
+            *added_by_agent.entry(agent.clone()).or_default() += 1;
+        }
+
+        let still_waiting = match event.event {
+            EventKind::Add | EventKind::Update => item.status == Status::Waiting,
+            EventKind::Remove => false,
+            EventKind::Focus => continue,
+        };
+
+        match (open.contains_key(&key), still_waiting) {
+            (false, true) => {
+                if recent {
+                    *hours.entry(crate::time::hour_of_day(event.ts)).or_default() += 1;
+                }
+                open.insert(key, (event.ts, proj, agent));
+            }
+            (true, false) => {
+                let (start, proj, agent) = open.remove(&key).expect("open wait");
+                if recent {
+                    waits.push(Wait {
+                        proj,
+                        agent,
+                        secs: event.ts.saturating_sub(start),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut all: Vec<u64> = waits.iter().map(|w| w.secs).collect();
+    all.sort_unstable();
+
+    let mut busiest_hours: Vec<(u8, usize)> = hours.into_iter().collect();
+    busiest_hours.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    Stats {
+        events: counted,
+        median_wait: percentile(&all, 50),
+        p90_wait: percentile(&all, 90),
+        by_proj: group(&waits, &added_by_proj, |w| &w.proj),
+        by_agent: group(&waits, &added_by_agent, |w| &w.agent),
+        busiest_hours,
+        longest_waiting: longest_waiting(inbox, now),
+    }
+}
+
+/// Build per-group stats, sorted by total time waited (descending)
+fn group(
+    waits: &[Wait],
+    added: &HashMap<String, usize>,
+    key: impl Fn(&Wait) -> &String,
+) -> Vec<GroupStats> {
+    let mut secs: HashMap<&String, Vec<u64>> = HashMap::new();
+    for wait in waits {
+        secs.entry(key(wait)).or_default().push(wait.secs);
+    }
+
+    let mut names: Vec<&String> = added.keys().chain(secs.keys().copied()).collect();
+    names.sort();
+    names.dedup();
+
+    let mut groups: Vec<GroupStats> = names
+        .into_iter()
+        .map(|name| {
+            let mut s = secs.get(name).cloned().unwrap_or_default();
+            s.sort_unstable();
+            GroupStats {
+                name: name.clone(),
+                added: added.get(name).copied().unwrap_or(0),
+                waits: s.len(),
+                median_wait: percentile(&s, 50),
+                p90_wait: percentile(&s, 90),
+                total_wait: s.iter().sum(),
+            }
+        })
+        .collect();
+    groups.sort_by(|a, b| b.total_wait.cmp(&a.total_wait).then(a.name.cmp(&b.name)));
+    groups
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[u64], pct: usize) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
+
+fn longest_waiting(inbox: &Inbox, now: u64) -> Option<LongestWaiting> {
+    inbox
+        .items
+        .iter()
+        .filter(|i| i.status == Status::Waiting)
+        .filter_map(|i| i.since().map(|since| (i, now.saturating_sub(since))))
+        .max_by_key(|(_, waiting)| *waiting)
+        .map(|(item, waiting)| LongestWaiting {
+            msg: item.msg().to_string(),
+            proj: item.proj().map(str::to_string),
+            pane: item.pane_id(),
+            waiting,
+        })
+}
+
+/// Render stats as plain-text tables
+pub fn render_text(stats: &Stats) -> String {
+    let fmt = |s: Option<u64>| s.map(crate::time::format_duration).unwrap_or("-".into());
+    let mut out = String::new();
+
+    out.push_str(&format!(
+        "{} events, median wait {}, p90 wait {}\n",
+        stats.events,
+        fmt(stats.median_wait),
+        fmt(stats.p90_wait)
+    ));
+
+    for (title, groups) in [("Project", &stats.by_proj), ("Agent", &stats.by_agent)] {
+        out.push_str(&format!(
+            "\n{:<20} {:>6} {:>6} {:>8} {:>8} {:>8}\n",
+            title, "added", "waits", "median", "p90", "total"
+        ));
+        for g in groups {
+            out.push_str(&format!(
+                "{:<20} {:>6} {:>6} {:>8} {:>8} {:>8}\n",
+                g.name,
+                g.added,
+                g.waits,
+                fmt(g.median_wait),
+                fmt(g.p90_wait),
+                crate::time::format_duration(g.total_wait)
+            ));
+        }
+    }
+
+    if !stats.busiest_hours.is_empty() {
+        let hours: Vec<String> = stats
+            .busiest_hours
+            .iter()
+            .take(3)
+            .map(|(h, n)| format!("{:02}:00 ({})", h, n))
+            .collect();
+        out.push_str(&format!("\nBusiest hours (UTC): {}\n", hours.join(", ")));
+    }
+
+    if let Some(ref l) = stats.longest_waiting {
+        out.push_str(&format!(
+            "Longest waiting: {} [{}] for {}\n",
+            l.msg,
+            l.proj.as_deref().unwrap_or("no project"),
+            crate::time::format_duration(l.waiting)
+        ));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::make_item, InboxItem};
+
+    fn event(ts: u64, kind: EventKind, item: &InboxItem) -> Event {
+        Event {
+            ts,
+            event: kind,
+            item: item.clone(),
+        }
+    }
+
+    #[test]
+    fn percentile_nearest_rank() {
+        assert_eq!(percentile(&[], 50), None);
+        assert_eq!(percentile(&[7], 90), Some(7));
+        assert_eq!(percentile(&[1, 2, 3, 4], 50), Some(2));
+        assert_eq!(percentile(&(1..=10).collect::<Vec<_>>(), 90), Some(9));
+    }
+
+    #[test]
+    fn waits_close_on_working_or_remove() {
+        let waiting = make_item("claude: Question", 1, "tael", None, Status::Waiting);
+        let working = make_item("claude: Busy", 1, "tael", None, Status::Working);
+        let other = make_item("codex: Review", 2, "k3s", None, Status::Waiting);
+
+        let events = vec![
+            event(0, EventKind::Add, &waiting),
+            event(60, EventKind::Update, &working),
+            event(100, EventKind::Add, &other),
+            event(400, EventKind::Remove, &other),
+        ];
+        let stats = compute(&events, &Inbox::new(), 1000, 0);
+
+        assert_eq!(stats.events, 4);
+        assert_eq!(stats.median_wait, Some(60));
+        assert_eq!(stats.p90_wait, Some(300));
+        assert_eq!(stats.by_proj[0].name, "k3s");
+        assert_eq!(stats.by_proj[0].total_wait, 300);
+        assert_eq!(stats.by_proj[1].name, "tael");
+        assert_eq!(stats.by_proj[1].added, 1);
+        assert_eq!(stats.by_agent[1].name, "claude");
+        assert_eq!(stats.busiest_hours, vec![(0, 2)]);
+    }
+
+    #[test]
+    fn items_sharing_a_pane_wait_separately() {
+        let with_id = |msg: &str, id: &str| {
+            let mut item = make_item(msg, 1, "tael", None, Status::Waiting);
+            item.attrs.insert("id".to_string(), id.to_string());
+            item
+        };
+        let (a, b) = (with_id("a", "aaaa"), with_id("b", "bbbb"));
+
+        let events = vec![
+            event(0, EventKind::Add, &a),
+            event(100, EventKind::Add, &b),
+            event(200, EventKind::Remove, &a),
+            event(500, EventKind::Remove, &b),
+        ];
+        let stats = compute(&events, &Inbox::new(), 1000, 0);
+        assert_eq!(stats.median_wait, Some(200));
+        assert_eq!(stats.p90_wait, Some(400));
+        assert_eq!(stats.by_proj[0].total_wait, 600);
+    }
+
+    #[test]
+    fn longest_waiting_uses_since() {
+        let mut old = make_item("old", 1, "tael", None, Status::Waiting);
+        old.attrs.insert("since".to_string(), "100".to_string());
+        let mut new = make_item("new", 2, "tael", None, Status::Waiting);
+        new.attrs.insert("since".to_string(), "900".to_string());
+        let inbox = Inbox {
+            items: vec![new, old],
+        };
+
+        let stats = compute(&[], &inbox, 1000, 0);
+        let longest = stats.longest_waiting.unwrap();
+        assert_eq!(longest.msg, "old");
+        assert_eq!(longest.waiting, 900);
+    }
+
+    #[test]
+    fn waits_belong_to_the_opening_event_and_count_when_closed() {
+        let waiting = make_item("claude: Question", 1, "tael", None, Status::Waiting);
+        let moved = make_item("codex: Busy", 1, "k3s", None, Status::Working);
+
+        let events = vec![
+            event(0, EventKind::Add, &waiting),
+            event(600, EventKind::Update, &moved),
+        ];
+        let stats = compute(&events, &Inbox::new(), 1000, 0);
+        assert_eq!(stats.by_proj[0].name, "tael");
+        assert_eq!(stats.by_proj[0].total_wait, 600);
+        assert_eq!(stats.by_agent[0].name, "claude");
+
+        // Opened before the cutoff, closed after it: still counted in full
+        let stats = compute(&events, &Inbox::new(), 1000, 300);
+        assert_eq!(stats.events, 1);
+        assert_eq!(stats.median_wait, Some(600));
+        assert_eq!(stats.by_proj[0].added, 0);
+        assert!(stats.busiest_hours.is_empty());
+    }
+}
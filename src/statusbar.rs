@@ -0,0 +1,197 @@
+//! Compact inbox summaries for status bars (tmux, zjstatus, waybar, polybar)
+
+use std::collections::BTreeMap;
+
+use crate::{Inbox, Status};
+
+/// Default template for `tael status`
+pub const DEFAULT_FORMAT: &str = "⏳{waiting} ⚙{working}";
+
+/// Render a status bar summary.
+///
+/// `format` is a preset (`waybar`, `tmux`, `polybar`) or a template with
+/// `{waiting}`, `{working}`, `{total}`, `{overdue}` and `{projects}`
+/// placeholders.
+pub fn render(inbox: &Inbox, format: &str) -> String {
+    render_flashing(inbox, format, 0)
+}
+
+/// Render a summary that flags `overdue` urgent items (see `escalate`):
+/// a blinking red count for tmux and polybar, the "urgent" class for waybar
+pub fn render_flashing(inbox: &Inbox, format: &str, overdue: usize) -> String {
+    let waiting = inbox.count(Status::Waiting);
+    let working = inbox.count(Status::Working);
+
+    match format {
+        "waybar" => {
+            let class = if overdue > 0 {
+                "urgent"
+            } else if waiting > 0 {
+                "waiting"
+            } else if working > 0 {
+                "working"
+            } else {
+                "empty"
+            };
+            let tooltip: Vec<String> = inbox
+                .items
+                .iter()
+                .map(|i| match i.proj() {
+                    Some(proj) => format!("{} {}: {}", i.status.to_icon(), proj, i.msg()),
+                    None => format!("{} {}", i.status.to_icon(), i.msg()),
+                })
+                .collect();
+            serde_json::json!({
+                "text": template(inbox, DEFAULT_FORMAT, overdue),
+                "tooltip": tooltip.join("\n"),
+                "class": class,
+                "alt": class,
+            })
+            .to_string()
+        }
+        // tmux status-right and zjstatus share the #[fg=...] style syntax
+        "tmux" => styled(waiting, working, overdue, |role, text| {
+            let style = match role {
+                Role::Overdue => "fg=red,bold,blink",
+                Role::Waiting => "fg=yellow",
+                Role::Working => "fg=blue",
+            };
+            format!("#[{}]{}#[default]", style, text)
+        }),
+        "polybar" => styled(waiting, working, overdue, |role, text| {
+            let color = match role {
+                Role::Overdue => "#e06c75",
+                Role::Waiting => "#e5c07b",
+                Role::Working => "#61afef",
+            };
+            format!("%{{F{}}}{}%{{F-}}", color, text)
+        }),
+        other => template(inbox, other, overdue),
+    }
+}
+
+/// What a styled part of the summary counts
+enum Role {
+    Overdue,
+    Waiting,
+    Working,
+}
+
+/// Only the non-zero counts, each wrapped in a style
+fn styled(
+    waiting: usize,
+    working: usize,
+    overdue: usize,
+    style: impl Fn(Role, &str) -> String,
+) -> String {
+    let mut parts = Vec::new();
+    if overdue > 0 {
+        parts.push(style(Role::Overdue, &format!("!{}", overdue)));
+    }
+    if waiting > 0 {
+        parts.push(style(
+            Role::Waiting,
+            &format!("{}{}", Status::Waiting.to_icon(), waiting),
+        ));
+    }
+    if working > 0 {
+        parts.push(style(
+            Role::Working,
+            &format!("{}{}", Status::Working.to_icon(), working),
+        ));
+    }
+    parts.join(" ")
+}
+
+/// Fill in a user template
+fn template(inbox: &Inbox, format: &str, overdue: usize) -> String {
+    let waiting = inbox.count(Status::Waiting);
+    let working = inbox.count(Status::Working);
+
+    // Waiting items per project, e.g. "crucible:2 tael:1"
+    let mut by_proj: BTreeMap<&str, usize> = BTreeMap::new();
+    for item in inbox.items.iter().filter(|i| i.status == Status::Waiting) {
+        *by_proj
+            .entry(item.proj().unwrap_or("(no project)"))
+            .or_default() += 1;
+    }
+    let projects: Vec<String> = by_proj
+        .iter()
+        .map(|(proj, n)| format!("{}:{}", proj, n))
+        .collect();
+
+    format
+        .replace("{waiting}", &waiting.to_string())
+        .replace("{working}", &working.to_string())
+        .replace("{total}", &inbox.items.len().to_string())
+        .replace("{overdue}", &overdue.to_string())
+        .replace("{projects}", &projects.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::make_item;
+
+    fn sample_inbox() -> Inbox {
+        Inbox {
+            items: vec![
+                make_item("claude: Auth", 1, "crucible", None, Status::Waiting),
+                make_item("claude: Review", 2, "crucible", None, Status::Waiting),
+                make_item("codex: Tests", 3, "tael", None, Status::Waiting),
+                make_item("indexer: Running", 4, "tael", None, Status::Working),
+            ],
+        }
+    }
+
+    #[test]
+    fn default_template() {
+        assert_eq!(render(&sample_inbox(), DEFAULT_FORMAT), "⏳3 ⚙1");
+        assert_eq!(render(&Inbox::new(), DEFAULT_FORMAT), "⏳0 ⚙0");
+    }
+
+    #[test]
+    fn custom_template_with_projects() {
+        let output = render(&sample_inbox(), "{total} items | {projects}");
+        assert_eq!(output, "4 items | crucible:2 tael:1");
+    }
+
+    #[test]
+    fn waybar_json() {
+        let output = render(&sample_inbox(), "waybar");
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(json["text"], "⏳3 ⚙1");
+        assert_eq!(json["class"], "waiting");
+        assert!(json["tooltip"]
+            .as_str()
+            .unwrap()
+            .contains("crucible: claude: Auth"));
+
+        let empty: serde_json::Value =
+            serde_json::from_str(&render(&Inbox::new(), "waybar")).unwrap();
+        assert_eq!(empty["class"], "empty");
+    }
+
+    #[test]
+    fn tmux_and_polybar_styles() {
+        let tmux = render(&sample_inbox(), "tmux");
+        assert_eq!(tmux, "#[fg=yellow]⏳3#[default] #[fg=blue]⚙1#[default]");
+        assert_eq!(render(&Inbox::new(), "tmux"), "");
+
+        let polybar = render(&sample_inbox(), "polybar");
+        assert!(polybar.starts_with("%{F#e5c07b}⏳3%{F-}"));
+    }
+
+    #[test]
+    fn overdue_items_flash() {
+        let inbox = sample_inbox();
+        assert_eq!(
+            render_flashing(&inbox, "tmux", 1),
+            "#[fg=red,bold,blink]!1#[default] #[fg=yellow]⏳3#[default] #[fg=blue]⚙1#[default]"
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&render_flashing(&inbox, "waybar", 2)).unwrap();
+        assert_eq!(json["class"], "urgent");
+        assert_eq!(render_flashing(&inbox, "{overdue}/{waiting}", 2), "2/3");
+    }
+}
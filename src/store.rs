@@ -0,0 +1,235 @@
+//! Inbox storage: direct file access, or a running daemon when available
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::daemon::Client;
+use crate::history::{self, Event, EventKind};
+use crate::{file, Inbox, InboxItem};
+
+/// Where inbox reads and writes go
+pub enum Store {
+    /// Load/modify/save the inbox file directly
+    File(PathBuf),
+    /// Forward requests to `tael daemon` over its Unix socket
+    Daemon(Client),
+}
+
+impl Store {
+    /// Use the daemon for this inbox if one is listening, else the file
+    pub fn open(path: &Path) -> Self {
+        match Client::connect(path) {
+            Some(client) => Self::Daemon(client),
+            None => Self::File(path.to_path_buf()),
+        }
+    }
+
+    /// Load the current inbox
+    pub fn load(&mut self) -> io::Result<Inbox> {
+        self.daemon_or_file(|client| client.list(), file::load)
+    }
+
+    /// Add or update an item, returning the replaced item if any
+    pub fn add(&mut self, item: InboxItem) -> io::Result<Option<InboxItem>> {
+        let sent = item.clone();
+        self.daemon_or_file(
+            |client| client.add(sent),
+            |path| add_to(&mut file::load(path)?, path, item),
+        )
+    }
+
+    /// Store items exactly as given (see `Inbox::put`) with one save
+    pub fn put_many(&mut self, items: Vec<InboxItem>) -> io::Result<()> {
+        let sent = items.clone();
+        self.daemon_or_file(
+            |client| client.put_many(sent),
+            |path| put_many_to(&mut file::load(path)?, path, items),
+        )
+    }
+
+    /// Remove all items for a pane ID, returning them
+    pub fn remove(&mut self, pane_id: u32) -> io::Result<Vec<InboxItem>> {
+        self.daemon_or_file(
+            |client| client.remove(pane_id),
+            |path| remove_from(&mut file::load(path)?, path, pane_id),
+        )
+    }
+
+    /// Remove an item by id, returning it if found
+    pub fn remove_id(&mut self, id: &str) -> io::Result<Option<InboxItem>> {
+        self.daemon_or_file(
+            |client| client.remove_id(id),
+            |path| remove_id_from(&mut file::load(path)?, path, id),
+        )
+    }
+
+    /// Remove items by id, returning the ones found
+    pub fn remove_many(&mut self, ids: &[String]) -> io::Result<Vec<InboxItem>> {
+        self.daemon_or_file(
+            |client| {
+                let mut removed = Vec::new();
+                for id in ids {
+                    removed.extend(client.remove_id(id)?);
+                }
+                Ok(removed)
+            },
+            |path| remove_many_from(&mut file::load(path)?, path, ids),
+        )
+    }
+
+    /// Remove all items except pinned ones, returning the removed items
+    pub fn clear(&mut self) -> io::Result<Vec<InboxItem>> {
+        self.daemon_or_file(
+            |client| client.clear(),
+            |path| clear(&mut file::load(path)?, path),
+        )
+    }
+
+    /// Mark an item seen if it still shows `msg` (without a history event,
+    /// since nothing about the item changed for the agent), returning it if so
+    pub fn mark_seen(&mut self, id: &str, msg: &str) -> io::Result<Option<InboxItem>> {
+        self.daemon_or_file(
+            |client| client.mark_seen(id, msg),
+            |path| mark_seen_in(&mut file::load(path)?, path, id, msg),
+        )
+    }
+
+    /// Run `op` on the daemon; if the connection fails (e.g. the daemon
+    /// exited), switch to the file for good and run `fallback` instead
+    fn daemon_or_file<T>(
+        &mut self,
+        op: impl FnOnce(&mut Client) -> io::Result<T>,
+        fallback: impl FnOnce(&Path) -> io::Result<T>,
+    ) -> io::Result<T> {
+        if let Self::Daemon(client) = self {
+            match op(client) {
+                Err(e) if e.kind() != io::ErrorKind::Other => {
+                    *self = Self::File(client.inbox_path().to_path_buf());
+                }
+                result => return result,
+            }
+        }
+        match self {
+            Self::File(path) => fallback(path),
+            Self::Daemon(_) => unreachable!("daemon connection replaced above"),
+        }
+    }
+}
+
+/// Upsert into an in-memory inbox, save it and record history
+pub(crate) fn add_to(
+    inbox: &mut Inbox,
+    path: &Path,
+    item: InboxItem,
+) -> io::Result<Option<InboxItem>> {
+    let replaced = inbox.upsert(item.clone());
+    file::save(path, inbox)?;
+    let kind = match replaced {
+        Some(_) => EventKind::Update,
+        None => EventKind::Add,
+    };
+    history::record(path, &[Event::new(kind, &item)]);
+    Ok(replaced)
+}
+
+/// Put items into an in-memory inbox, save it once and record history
+pub(crate) fn put_many_to(inbox: &mut Inbox, path: &Path, items: Vec<InboxItem>) -> io::Result<()> {
+    let mut events = Vec::new();
+    for item in items {
+        let kind = match inbox.put(item.clone()) {
+            Some(_) => EventKind::Update,
+            None => EventKind::Add,
+        };
+        events.push(Event::new(kind, &item));
+    }
+    if !events.is_empty() {
+        file::save(path, inbox)?;
+        history::record(path, &events);
+    }
+    Ok(())
+}
+
+/// Remove from an in-memory inbox, save it and record history
+pub(crate) fn remove_from(
+    inbox: &mut Inbox,
+    path: &Path,
+    pane_id: u32,
+) -> io::Result<Vec<InboxItem>> {
+    let removed = inbox.remove(pane_id);
+    if !removed.is_empty() {
+        file::save(path, inbox)?;
+        let events: Vec<_> = removed
+            .iter()
+            .map(|item| Event::new(EventKind::Remove, item))
+            .collect();
+        history::record(path, &events);
+    }
+    Ok(removed)
+}
+
+/// Remove by id from an in-memory inbox, save it and record history
+pub(crate) fn remove_id_from(
+    inbox: &mut Inbox,
+    path: &Path,
+    id: &str,
+) -> io::Result<Option<InboxItem>> {
+    let removed = inbox.remove_id(id);
+    if let Some(ref item) = removed {
+        file::save(path, inbox)?;
+        history::record(path, &[Event::new(EventKind::Remove, item)]);
+    }
+    Ok(removed)
+}
+
+/// Remove several items by id from an in-memory inbox with one save
+pub(crate) fn remove_many_from(
+    inbox: &mut Inbox,
+    path: &Path,
+    ids: &[String],
+) -> io::Result<Vec<InboxItem>> {
+    let removed = inbox.remove_many(ids);
+    if !removed.is_empty() {
+        file::save(path, inbox)?;
+        let events: Vec<_> = removed
+            .iter()
+            .map(|item| Event::new(EventKind::Remove, item))
+            .collect();
+        history::record(path, &events);
+    }
+    Ok(removed)
+}
+
+/// Remove unpinned items from an in-memory inbox, save it and record history
+pub(crate) fn clear(inbox: &mut Inbox, path: &Path) -> io::Result<Vec<InboxItem>> {
+    let removed = inbox.clear();
+    file::save(path, inbox)?;
+    let events: Vec<_> = removed
+        .iter()
+        .map(|item| Event::new(EventKind::Remove, item))
+        .collect();
+    history::record(path, &events);
+    Ok(removed)
+}
+
+/// Mark an item in an in-memory inbox seen and save it (no history)
+pub(crate) fn mark_seen_in(
+    inbox: &mut Inbox,
+    path: &Path,
+    id: &str,
+    msg: &str,
+) -> io::Result<Option<InboxItem>> {
+    let Some(item) = inbox
+        .items
+        .iter_mut()
+        .find(|i| i.id() == Some(id) && i.msg() == msg)
+    else {
+        return Ok(None);
+    };
+    let unseen = !item.is_seen();
+    item.attrs.insert("seen".to_string(), "true".to_string());
+    let item = item.clone();
+    if unseen {
+        file::save(path, inbox)?;
+    }
+    Ok(Some(item))
+}
@@ -0,0 +1,137 @@
+//! Timestamp helpers (unix seconds, no timezone database)
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Current time as unix seconds
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Parse a duration like "30s", "15m", "2h" or "1d" into seconds.
+/// A bare number is treated as seconds.
+pub fn parse_duration(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let (num, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(idx) => s.split_at(idx),
+        None => (s, "s"),
+    };
+    let n: u64 = num.parse().ok()?;
+    let mult = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+    n.checked_mul(mult)
+}
+
+/// Parse a point in time: a duration ago ("2h") or an absolute unix timestamp
+pub fn parse_time(s: &str, now: u64) -> Option<u64> {
+    // Anything longer than a plausible relative offset is an absolute timestamp
+    match s.trim().parse::<u64>() {
+        Ok(ts) if ts >= 1_000_000_000 => Some(ts),
+        _ => parse_duration(s).map(|d| now.saturating_sub(d)),
+    }
+}
+
+/// Parse an end time: a duration from now ("30m") or an absolute unix timestamp
+pub fn parse_until(s: &str, now: u64) -> Option<u64> {
+    match s.trim().parse::<u64>() {
+        Ok(ts) if ts >= 1_000_000_000 => Some(ts),
+        _ => parse_duration(s).and_then(|d| now.checked_add(d)),
+    }
+}
+
+/// Format seconds as a compact duration ("45s", "12m", "3h05m", "2d04h")
+pub fn format_duration(secs: u64) -> String {
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 60 * 60 => format!("{}m", s / 60),
+        s if s < 24 * 60 * 60 => format!("{}h{:02}m", s / 3600, (s % 3600) / 60),
+        s => format!("{}d{:02}h", s / 86400, (s % 86400) / 3600),
+    }
+}
+
+/// Format unix seconds as "YYYY-MM-DD HH:MM:SS" (UTC)
+pub fn format_timestamp(ts: u64) -> String {
+    let days = (ts / 86400) as i64;
+    let rem = ts % 86400;
+    let (y, m, d) = civil_from_days(days);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        y,
+        m,
+        d,
+        rem / 3600,
+        (rem % 3600) / 60,
+        rem % 60
+    )
+}
+
+/// Hour of day (0-23, UTC) for a unix timestamp
+pub fn hour_of_day(ts: u64) -> u8 {
+    ((ts % 86400) / 3600) as u8
+}
+
+/// Convert days since 1970-01-01 to a (year, month, day) civil date
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    // Howard Hinnant's days_from_civil inverse
+    let z = z + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let y = yoe + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (if m <= 2 { y + 1 } else { y }, m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_durations() {
+        assert_eq!(parse_duration("45"), Some(45));
+        assert_eq!(parse_duration("30s"), Some(30));
+        assert_eq!(parse_duration("15m"), Some(900));
+        assert_eq!(parse_duration("2h"), Some(7200));
+        assert_eq!(parse_duration("1d"), Some(86400));
+        assert_eq!(parse_duration("1y"), None);
+        assert_eq!(parse_duration("m"), None);
+        assert_eq!(parse_duration("99999999999999999w"), None);
+    }
+
+    #[test]
+    fn parse_relative_and_absolute_times() {
+        let now = 1_800_000_000;
+        assert_eq!(parse_time("1h", now), Some(now - 3600));
+        assert_eq!(parse_time("1700000000", now), Some(1_700_000_000));
+        assert_eq!(parse_until("30m", now), Some(now + 1800));
+        assert_eq!(parse_until("1900000000", now), Some(1_900_000_000));
+        assert_eq!(parse_until("soon", now), None);
+        assert_eq!(parse_until("213503982334601d", now), None);
+    }
+
+    #[test]
+    fn format_timestamps() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00");
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14 22:13:20");
+        assert_eq!(hour_of_day(1_700_000_000), 22);
+    }
+
+    #[test]
+    fn format_durations() {
+        assert_eq!(format_duration(5), "5s");
+        assert_eq!(format_duration(125), "2m");
+        assert_eq!(format_duration(3 * 3600 + 5 * 60), "3h05m");
+        assert_eq!(format_duration(2 * 86400 + 4 * 3600), "2d04h");
+    }
+}
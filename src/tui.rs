@@ -1,180 +1,1445 @@
 //! TUI rendering for tael using ratatui
 
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::io::{self, stdout};
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+use std::time::{Duration, Instant};
 
 // Use crossterm directly (with use-dev-tty feature) instead of ratatui's re-export
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+        KeyModifiers, MouseButton, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 
 use ratatui::{
-    backend::CrosstermBackend,
-    layout::{Constraint, Layout},
+    backend::{Backend, CrosstermBackend},
+    layout::{Constraint, Layout, Position, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{List, ListItem, ListState, Paragraph},
+    widgets::{Clear, List, ListItem, ListState, Paragraph},
     Frame, Terminal,
 };
 
 use crate::config::Config;
-use crate::Inbox;
+use crate::escalate::{self, Action};
+use crate::history::{self, EventKind};
+use crate::hooks::HookEvent;
+use crate::sort::Sort;
+use crate::store::Store;
+use crate::time::format_duration;
+use crate::undo;
+use crate::watch::Change;
+use crate::{Inbox, InboxItem, Priority, Status};
 
-/// Run interactive TUI mode
-pub fn run_interactive(config: &Config, group_by: &[String]) -> io::Result<()> {
-    let path = crate::file::default_path();
-    let inbox = crate::file::load(&path)?;
+/// Run interactive TUI mode on the inbox at `path`. With `all_sessions`,
+/// shows every inbox file in its directory grouped by session.
+pub fn run_interactive(
+    config: &Config,
+    path: &Path,
+    group_by: &[String],
+    all_sessions: bool,
+) -> io::Result<()> {
+    let mut app = App::open(path, group_by, all_sessions)?;
+    app.set_sort(config.sort.clone());
+    app.editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|e| !e.trim().is_empty());
 
     // Manual terminal setup using crossterm directly (with use-dev-tty feature)
     enable_raw_mode()?;
     let mut stdout = stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new(inbox, path.to_path_buf(), group_by.to_vec());
+    let result = run_app(&mut terminal, &mut app, config, &mut TerminalEvents);
 
-    // Event loop
-    let result = loop {
-        terminal.draw(|frame| draw(frame, &mut app))?;
+    // Restore terminal
+    execute!(
+        terminal.backend_mut(),
+        DisableMouseCapture,
+        LeaveAlternateScreen
+    )?;
+    disable_raw_mode()?;
+
+    // Focus after restoring the terminal; switches session first for items
+    // from other sessions
+    if let Some(item) = result? {
+        let _ = config.focus_item(&item);
+    }
+    Ok(())
+}
+
+/// Source of input events for the TUI event loop
+pub trait EventSource {
+    /// Wait for the next event; `None` ends the loop
+    fn next_event(&mut self) -> io::Result<Option<Event>>;
+
+    /// Hand the terminal to an external program (e.g. `$EDITOR`) until it
+    /// exits. Sources without a terminal can't.
+    fn suspend(&mut self, _cmd: &mut Command) -> io::Result<ExitStatus> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "no terminal to run an editor in",
+        ))
+    }
+
+    /// Ring the terminal bell, if there is one
+    fn bell(&mut self) {}
+}
+
+/// Events from the terminal
+pub struct TerminalEvents;
+
+impl EventSource for TerminalEvents {
+    fn next_event(&mut self) -> io::Result<Option<Event>> {
+        // Idle ticks are a resize to the current size, so the loop gets to
+        // run periodic checks without input
+        let start = Instant::now();
+        loop {
+            if event::poll(Duration::from_millis(100))? {
+                return event::read().map(Some);
+            }
+            if start.elapsed() >= TICK_INTERVAL {
+                let (width, height) = crossterm::terminal::size()?;
+                return Ok(Some(Event::Resize(width, height)));
+            }
+        }
+    }
+
+    fn bell(&mut self) {
+        use std::io::Write;
+        let mut out = stdout();
+        let _ = out.write_all(b"\x07").and_then(|_| out.flush());
+    }
+
+    fn suspend(&mut self, cmd: &mut Command) -> io::Result<ExitStatus> {
+        disable_raw_mode()?;
+        execute!(stdout(), DisableMouseCapture, LeaveAlternateScreen)?;
+        let status = cmd.status();
+        execute!(stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        enable_raw_mode()?;
+        status
+    }
+}
+
+/// Scripted events, e.g. for driving the TUI in tests
+impl<I: Iterator<Item = Event>> EventSource for I {
+    fn next_event(&mut self) -> io::Result<Option<Event>> {
+        Ok(self.next())
+    }
+}
+
+/// How often to run periodic checks (expired snoozes, overdue urgent items)
+const TICK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Event loop: draw, then handle one event, until quit or the events run out.
+/// Returns the item to focus if the user pressed Enter on one.
+fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    config: &Config,
+    events: &mut impl EventSource,
+) -> io::Result<Option<InboxItem>> {
+    loop {
+        if app.last_tick.is_none_or(|t| t.elapsed() >= TICK_INTERVAL) {
+            app.last_tick = Some(Instant::now());
+            let now = crate::time::now();
+            app.wake_expired(now);
+            if app.escalate(config, now) {
+                events.bell();
+            }
+        }
+
+        // Showing an item's details counts as looking at it
+        if app.details {
+            app.mark_seen();
+        }
+        terminal.draw(|frame| draw(frame, app))?;
+
+        let Some(evt) = events.next_event()? else {
+            return Ok(None);
+        };
 
-        if event::poll(Duration::from_millis(100))? {
-            let evt = event::read()?;
+        match evt {
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                app.message = None;
 
-            if let Event::Key(key) = evt {
-                if key.kind != KeyEventKind::Press {
+                // A pending confirmation takes the next key: y accepts, anything else cancels
+                if let Some(confirm) = app.confirm.take() {
+                    if key.code == KeyCode::Char('y') {
+                        for removed in app.run_confirmed(confirm) {
+                            let _ = config.hooks.fire(HookEvent::Remove, &removed);
+                        }
+                    }
                     continue;
                 }
+
+                // The inline editor takes keys until Enter saves or Esc cancels
+                if let Some(mut edit) = app.edit.take() {
+                    match key.code {
+                        KeyCode::Enter => {
+                            if let Some(item) = app.save_edit(edit.item, &edit.input.text) {
+                                let _ = config.hooks.fire(HookEvent::Update, &item);
+                            }
+                        }
+                        KeyCode::Esc => {}
+                        _ => {
+                            edit.input.handle(key);
+                            app.edit = Some(edit);
+                        }
+                    }
+                    continue;
+                }
+
+                // The new-item form takes keys until Enter adds or Esc cancels
+                if let Some(mut form) = app.add.take() {
+                    match key.code {
+                        KeyCode::Enter => match form.item() {
+                            Ok(item) => {
+                                if let Some((event, item)) = app.add_item(item) {
+                                    let _ = config.hooks.fire(event, &item);
+                                }
+                            }
+                            Err(e) => {
+                                form.error = Some(e);
+                                app.add = Some(form);
+                            }
+                        },
+                        KeyCode::Esc => {}
+                        _ => {
+                            form.handle(key);
+                            app.add = Some(form);
+                        }
+                    }
+                    continue;
+                }
+
+                // Any key closes the help overlay
+                if std::mem::take(&mut app.help) {
+                    continue;
+                }
+
+                // Second key of a `z` sequence: vim-style folds and snoozing
+                if std::mem::take(&mut app.pending_z) {
+                    let snooze = match key.code {
+                        KeyCode::Char('a') => {
+                            app.toggle_fold();
+                            continue;
+                        }
+                        KeyCode::Char('z') => Some(30 * 60),
+                        KeyCode::Char(c @ '1'..='9') => Some(u64::from(c as u8 - b'0') * 60 * 60),
+                        KeyCode::Char('w') => None,
+                        _ => continue,
+                    };
+                    for changed in app.snooze(snooze) {
+                        let _ = config.hooks.fire(HookEvent::Update, &changed);
+                    }
+                    continue;
+                }
+
                 match (key.code, key.modifiers) {
-                    (KeyCode::Char('q'), _) | (KeyCode::Esc, _) => break Ok(()),
-                    (KeyCode::Char('c'), KeyModifiers::CONTROL) => break Ok(()),
+                    (KeyCode::Esc, _) if app.visual_anchor.is_some() => app.visual_anchor = None,
+                    (KeyCode::Esc, _) if !app.marks.is_empty() => app.marks.clear(),
+                    (KeyCode::Char('q'), _) | (KeyCode::Esc, _) => return Ok(None),
+                    (KeyCode::Char('c'), KeyModifiers::CONTROL) => return Ok(None),
                     (KeyCode::Char('j'), _) | (KeyCode::Down, _) => app.next(),
                     (KeyCode::Char('k'), _) | (KeyCode::Up, _) => app.previous(),
-                    (KeyCode::Char('d'), _) => app.delete_selected(),
-                    (KeyCode::Char('r'), _) => app.reload(),
-                    (KeyCode::Char('p'), _) => {
-                        // Toggle pinned state - keeps floating pane always-on-top (Zellij only)
-                        if std::env::var("ZELLIJ").is_ok() {
-                            let _ = std::process::Command::new("zellij")
-                                .args(["action", "toggle-pane-pinned"])
-                                .status();
+                    (KeyCode::Char(' '), _) if app.selected_group.is_some() => app.toggle_fold(),
+                    (KeyCode::Char(' '), _) => app.toggle_mark(),
+                    (KeyCode::Char('v'), _) => app.toggle_visual(),
+                    (KeyCode::Char('z'), _) => {
+                        app.pending_z = true;
+                        app.message =
+                            Some("z: snooze 30m, 1-9: snooze hours, w: wake, a: fold".to_string());
+                    }
+                    (KeyCode::Char('d'), _) if !app.marked_items().is_empty() => {
+                        app.confirm_delete_marked()
+                    }
+                    (KeyCode::Char('d'), _) if app.selected_group.is_some() => {
+                        app.confirm_delete_group()
+                    }
+                    (KeyCode::Char('d'), _)
+                        if app.selected().is_some_and(|i| i.status == Status::Waiting) =>
+                    {
+                        app.confirm_delete_selected()
+                    }
+                    (KeyCode::Char('d'), _) => {
+                        if let Some(removed) = app.delete_selected() {
+                            let _ = config.hooks.fire(HookEvent::Remove, &removed);
+                        }
+                    }
+                    (KeyCode::Char('s'), _) => {
+                        for changed in app.toggle_status() {
+                            let _ = config.hooks.fire(HookEvent::Update, &changed);
+                        }
+                    }
+                    (KeyCode::Char('e'), _) => app.start_edit(),
+                    (KeyCode::Char('a'), _) => app.add = Some(AddForm::new()),
+                    (KeyCode::Char('E'), _) => {
+                        let edited = app.edit_externally(events);
+                        // The editor drew over the screen
+                        terminal.clear()?;
+                        if let Some(item) = edited {
+                            let _ = config.hooks.fire(HookEvent::Update, &item);
                         }
                     }
+                    (KeyCode::Tab, _) => {
+                        // Visit marked panes in turn, staying in the TUI
+                        let next = app.cycle_marked().then(|| focus_selected(app, config));
+                        if let Some(item) = next.flatten() {
+                            let _ = config.focus_item(&item);
+                        }
+                    }
+                    (KeyCode::Char('u'), _) => {
+                        for change in app.undo() {
+                            let (event, item) = HookEvent::for_change(&change);
+                            let _ = config.hooks.fire(event, item);
+                        }
+                    }
+                    (KeyCode::Char('r'), KeyModifiers::CONTROL) => {
+                        for change in app.redo() {
+                            let (event, item) = HookEvent::for_change(&change);
+                            let _ = config.hooks.fire(event, item);
+                        }
+                    }
+                    (KeyCode::Char('r'), _) => app.reload(),
+                    (KeyCode::Char('o'), _) => app.cycle_sort(),
+                    (KeyCode::Char('i'), _) => app.details = !app.details,
+                    (KeyCode::Char('?'), _) => app.help = true,
+                    // Toggle pinned state - keeps floating pane always-on-top (Zellij only)
+                    (KeyCode::Char('p'), _) if std::env::var("ZELLIJ").is_ok() => {
+                        let _ = std::process::Command::new("zellij")
+                            .args(["action", "toggle-pane-pinned"])
+                            .status();
+                    }
+                    (KeyCode::Enter, _) if app.selected_group.is_some() => app.toggle_fold(),
                     (KeyCode::Enter, _) => {
-                        if let Some(pane_id) = app.selected_pane_id() {
-                            // Restore terminal before focusing
-                            execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-                            disable_raw_mode()?;
-                            let _ = config.focus_pane(pane_id);
-                            return Ok(());
+                        if let Some(item) = focus_selected(app, config) {
+                            return Ok(Some(item));
                         }
                     }
                     _ => {}
                 }
             }
+            Event::Mouse(mouse) => match mouse.kind {
+                MouseEventKind::ScrollDown => app.next(),
+                MouseEventKind::ScrollUp => app.previous(),
+                MouseEventKind::Down(MouseButton::Left) => {
+                    match app.row_at(mouse.column, mouse.row) {
+                        Some(row @ Row::Header { .. }) => {
+                            app.select_row(&row);
+                            app.toggle_fold();
+                        }
+                        Some(Row::Item(idx)) => {
+                            // A single click only selects
+                            if !app.click(idx) {
+                                continue;
+                            }
+                            if let Some(item) = focus_selected(app, config) {
+                                return Ok(Some(item));
+                            }
+                        }
+                        None => {}
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
         }
-    };
+    }
+}
 
-    // Restore terminal
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    disable_raw_mode()?;
-    result
+/// Record and announce focusing the selected item (marking it seen),
+/// returning it if it has a pane
+fn focus_selected(app: &mut App, config: &Config) -> Option<InboxItem> {
+    app.selected().filter(|i| i.pane_id().is_some())?;
+    app.mark_seen();
+    let item = app.selected()?;
+    history::record(
+        &app.item_path(item),
+        &[history::Event::new(EventKind::Focus, item)],
+    );
+    let _ = config.hooks.fire(HookEvent::Focus, item);
+    Some(item.clone())
+}
+
+/// Two clicks on the same row within this interval focus the item
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// An action waiting for y/n in the status line
+#[derive(Debug, Clone, PartialEq)]
+enum Confirm {
+    /// Delete items (indices into inbox.items)
+    Delete { items: Vec<usize>, prompt: String },
+}
+
+/// A single-line text input shown in the status line
+#[derive(Debug, Clone, Default, PartialEq)]
+struct LineInput {
+    text: String,
+    /// Cursor position in chars
+    cursor: usize,
+}
+
+impl LineInput {
+    fn new(text: String) -> Self {
+        let cursor = text.chars().count();
+        Self { text, cursor }
+    }
+
+    /// Byte offset of the char at `pos`
+    fn byte_at(&self, pos: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(pos)
+            .map_or(self.text.len(), |(i, _)| i)
+    }
+
+    /// Apply an editing key (readline-style), ignoring anything else
+    fn handle(&mut self, key: KeyEvent) {
+        let len = self.text.chars().count();
+        match (key.code, key.modifiers) {
+            (KeyCode::Char('a'), KeyModifiers::CONTROL) | (KeyCode::Home, _) => self.cursor = 0,
+            (KeyCode::Char('e'), KeyModifiers::CONTROL) | (KeyCode::End, _) => self.cursor = len,
+            (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
+                self.text.drain(..self.byte_at(self.cursor));
+                self.cursor = 0;
+            }
+            (KeyCode::Left, _) => self.cursor = self.cursor.saturating_sub(1),
+            (KeyCode::Right, _) => self.cursor = (self.cursor + 1).min(len),
+            (KeyCode::Backspace, _) if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.text.remove(self.byte_at(self.cursor));
+            }
+            (KeyCode::Delete, _) if self.cursor < len => {
+                self.text.remove(self.byte_at(self.cursor));
+            }
+            (KeyCode::Char(c), m) if !m.contains(KeyModifiers::CONTROL) => {
+                self.text.insert(self.byte_at(self.cursor), c);
+                self.cursor += 1;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// An item being edited inline as its markdown line
+#[derive(Debug, Clone, PartialEq)]
+struct Edit {
+    /// Index into inbox.items
+    item: usize,
+    input: LineInput,
+}
+
+/// Fields of the new-item form, in Tab order
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AddField {
+    Msg,
+    Status,
+    Attrs,
+}
+
+/// Lines taken by the new-item form
+const ADD_FORM_HEIGHT: u16 = 4;
+
+/// The form for creating an item from the TUI
+#[derive(Debug, Clone, PartialEq)]
+struct AddForm {
+    field: AddField,
+    msg: LineInput,
+    status: Status,
+    /// Shell-style `key=value` pairs
+    attrs: LineInput,
+    /// Why the last submit was rejected
+    error: Option<String>,
+}
+
+impl AddForm {
+    fn new() -> Self {
+        Self {
+            field: AddField::Msg,
+            msg: LineInput::default(),
+            status: Status::Waiting,
+            attrs: LineInput::default(),
+            error: None,
+        }
+    }
+
+    /// Handle a key other than Enter/Esc
+    fn handle(&mut self, key: KeyEvent) {
+        self.error = None;
+        self.field = match (key.code, self.field) {
+            (KeyCode::Tab | KeyCode::Down, AddField::Msg) => AddField::Status,
+            (KeyCode::Tab | KeyCode::Down, AddField::Status) => AddField::Attrs,
+            (KeyCode::Tab | KeyCode::Down, AddField::Attrs) => AddField::Msg,
+            (KeyCode::BackTab | KeyCode::Up, AddField::Msg) => AddField::Attrs,
+            (KeyCode::BackTab | KeyCode::Up, AddField::Status) => AddField::Msg,
+            (KeyCode::BackTab | KeyCode::Up, AddField::Attrs) => AddField::Status,
+            (_, field) => {
+                match field {
+                    AddField::Msg => self.msg.handle(key),
+                    AddField::Attrs => self.attrs.handle(key),
+                    AddField::Status => {
+                        if matches!(
+                            key.code,
+                            KeyCode::Char(' ') | KeyCode::Left | KeyCode::Right
+                        ) {
+                            self.status = self.status.toggled();
+                        }
+                    }
+                }
+                field
+            }
+        };
+    }
+
+    /// Build the item, or explain what's wrong
+    fn item(&self) -> Result<InboxItem, String> {
+        let mut attrs = HashMap::new();
+        let words = shell_words::split(&self.attrs.text).map_err(|e| format!("attrs: {}", e))?;
+        for word in words {
+            let (key, value) = word
+                .split_once('=')
+                .filter(|(key, _)| !key.is_empty())
+                .ok_or_else(|| format!("invalid attr '{}': expected key=value", word))?;
+            attrs.insert(key.to_string(), value.to_string());
+        }
+        let msg = self.msg.text.trim();
+        if msg.is_empty() {
+            return Err("msg is required".to_string());
+        }
+        attrs.insert("msg".to_string(), msg.to_string());
+        attrs.insert("since".to_string(), crate::time::now().to_string());
+        Ok(InboxItem::new(attrs, self.status))
+    }
+}
+
+/// An item as a markdown snippet for editing (see `render::render`)
+fn edit_snippet(item: InboxItem) -> String {
+    crate::render::render(&Inbox { items: vec![item] })
 }
 
 struct App {
     inbox: Inbox,
     /// Index into inbox.items (not the visual list)
     selected_item: Option<usize>,
+    /// Id of the selected group header, if the cursor is on one
+    selected_group: Option<String>,
     list_state: ListState,
-    path: std::path::PathBuf,
+    path: PathBuf,
     /// Grouping keys for display
     group_by: Vec<String>,
+    /// Directory of session inbox files when showing all sessions
+    sessions_dir: Option<PathBuf>,
+    /// Ids of collapsed groups (see `group_id`); kept across reloads
+    collapsed: HashSet<String>,
+    /// Where the list was last drawn and what each row showed, for mouse hits
+    list_area: Rect,
+    rows: Vec<Row>,
+    /// Time and item of the last click, to detect double-clicks
+    last_click: Option<(Instant, usize)>,
+    /// Action awaiting confirmation
+    confirm: Option<Confirm>,
+    /// One-off message for the status line (cleared on the next key)
+    message: Option<String>,
+    /// `z` was pressed and the next key completes the sequence
+    pending_z: bool,
+    /// Key help overlay shown (closed by any key)
+    help: bool,
+    /// Index of the first snoozed item; they're listed last, under their
+    /// own header
+    snoozed_from: usize,
+    /// Marked items (indices into inbox.items) for bulk actions
+    marks: BTreeSet<usize>,
+    /// Item where visual mode started; the range up to the cursor is marked
+    visual_anchor: Option<usize>,
+    /// Position in the marked items for focus cycling
+    cycle: usize,
+    /// Inbox files touched by each action this session, for undo/redo
+    /// (one action can span several session files)
+    undo_paths: Vec<Vec<PathBuf>>,
+    redo_paths: Vec<Vec<PathBuf>>,
+    /// Item being edited inline
+    edit: Option<Edit>,
+    /// New-item form, while open
+    add: Option<AddForm>,
+    /// Editor command for `E` ($VISUAL or $EDITOR)
+    editor: Option<String>,
+    /// Display order; `o` cycles through `sorts` (the configured one first)
+    sort: Sort,
+    sorts: Vec<Sort>,
+    /// Whether the details panel for the selected item is open (`i`)
+    details: bool,
+    /// When the periodic checks last ran
+    last_tick: Option<Instant>,
+    /// Status line alert while urgent items are overdue, and the waiting
+    /// stints that already rang the bell
+    alert: Option<String>,
+    rung: HashSet<String>,
+}
+
+/// Orders `o` cycles through after the configured one
+const SORT_PRESETS: &[&str] = &[crate::sort::DEFAULT, "-age", "priority,-age", "proj,-age"];
+
+/// Load one inbox (through the daemon if one is running), or every
+/// session's inbox when a sessions dir is given
+fn load_inbox(path: &Path, sessions_dir: Option<&Path>) -> io::Result<Inbox> {
+    match sessions_dir {
+        Some(dir) => crate::file::load_all(dir),
+        None => Store::open(path).load(),
+    }
 }
 
 impl App {
-    fn new(inbox: Inbox, path: std::path::PathBuf, group_by: Vec<String>) -> Self {
-        let selected_item = if inbox.is_empty() { None } else { Some(0) };
-        Self {
+    /// Load the inbox at `path` (or all sessions next to it) for display
+    fn open(path: &Path, group_by: &[String], all_sessions: bool) -> io::Result<Self> {
+        let sessions_dir = if all_sessions {
+            Some(crate::file::sessions_dir(path).to_path_buf())
+        } else {
+            None
+        };
+        let inbox = load_inbox(path, sessions_dir.as_deref())?;
+
+        let mut group_by = group_by.to_vec();
+        if all_sessions && group_by.is_empty() {
+            group_by.push("session".to_string());
+        }
+        let mut app = Self::new(inbox, path.to_path_buf(), group_by);
+        app.sessions_dir = sessions_dir;
+        Ok(app)
+    }
+
+    fn new(inbox: Inbox, path: PathBuf, group_by: Vec<String>) -> Self {
+        let mut app = Self {
             inbox,
-            selected_item,
+            selected_item: None,
+            selected_group: None,
             list_state: ListState::default(),
             path,
             group_by,
+            sessions_dir: None,
+            collapsed: HashSet::new(),
+            list_area: Rect::default(),
+            rows: Vec::new(),
+            last_click: None,
+            confirm: None,
+            message: None,
+            pending_z: false,
+            help: false,
+            snoozed_from: 0,
+            marks: BTreeSet::new(),
+            visual_anchor: None,
+            cycle: 0,
+            undo_paths: Vec::new(),
+            redo_paths: Vec::new(),
+            edit: None,
+            add: None,
+            editor: None,
+            sort: Sort::default(),
+            sorts: Vec::new(),
+            details: false,
+            last_tick: None,
+            alert: None,
+            rung: HashSet::new(),
+        };
+        // Snoozed items stay out of the way until asked for
+        app.collapsed.insert(SNOOZED.to_string());
+        app.set_sort(Sort::default());
+        app
+    }
+
+    /// Rows of the visual list for the current inbox and folds
+    fn visual_rows(&self) -> Vec<Row> {
+        visual_rows(
+            &self.inbox,
+            &self.group_by,
+            &self.collapsed,
+            self.snoozed_from,
+        )
+    }
+
+    /// Visual index of the cursor
+    fn cursor(&self, rows: &[Row]) -> Option<usize> {
+        rows.iter().position(|row| match row {
+            Row::Header { id, .. } => self.selected_group.as_ref() == Some(id),
+            Row::Item(i) => self.selected_group.is_none() && self.selected_item == Some(*i),
+        })
+    }
+
+    fn select_row(&mut self, row: &Row) {
+        match row {
+            Row::Header { id, .. } => self.selected_group = Some(id.clone()),
+            Row::Item(i) => {
+                self.selected_group = None;
+                self.selected_item = Some(*i);
+            }
+        }
+    }
+
+    /// Outermost collapsed group containing an item
+    fn collapsed_ancestor(&self, idx: usize) -> Option<String> {
+        let item = self.inbox.items.get(idx)?;
+        if idx >= self.snoozed_from {
+            return self
+                .collapsed
+                .contains(SNOOZED)
+                .then(|| SNOOZED.to_string());
         }
+        (0..self.group_by.len())
+            .map(|level| group_id(item, &self.group_by[..=level]))
+            .find(|id| self.collapsed.contains(id))
     }
 
     fn next(&mut self) {
-        if self.inbox.is_empty() {
+        let rows = self.visual_rows();
+        let target = self
+            .cursor(&rows)
+            .map_or(0, |pos| (pos + 1).min(rows.len().saturating_sub(1)));
+        if let Some(row) = rows.get(target) {
+            self.select_row(row);
+        }
+    }
+
+    fn previous(&mut self) {
+        let rows = self.visual_rows();
+        let target = self.cursor(&rows).map_or(0, |pos| pos.saturating_sub(1));
+        if let Some(row) = rows.get(target) {
+            self.select_row(row);
+        }
+    }
+
+    /// Keep the cursor on a visible row after groups or items change
+    fn fix_selection(&mut self) {
+        let rows = self.visual_rows();
+        if self.cursor(&rows).is_some() {
             return;
         }
-        self.selected_item = Some(match self.selected_item {
-            Some(i) => (i + 1).min(self.inbox.items.len() - 1),
-            None => 0,
+        // A hidden item moves the cursor onto its collapsed group
+        if let Some(id) = self.selected_item.and_then(|i| self.collapsed_ancestor(i)) {
+            self.selected_group = Some(id);
+            return;
+        }
+        // Otherwise the item now at the old position, or the last row
+        let old = self.selected_item.unwrap_or(0);
+        let row = rows
+            .iter()
+            .find(|row| matches!(row, Row::Item(i) if *i >= old))
+            .or(rows.last())
+            .cloned();
+        self.selected_group = None;
+        self.selected_item = None;
+        if let Some(row) = row {
+            self.select_row(&row);
+        }
+    }
+
+    /// Collapse or expand the group under the cursor (the selected item's
+    /// innermost group when the cursor is on an item)
+    fn toggle_fold(&mut self) {
+        let group = match (&self.selected_group, self.selected()) {
+            (Some(id), _) => id.clone(),
+            (None, Some(_)) if self.selected_item >= Some(self.snoozed_from) => SNOOZED.to_string(),
+            (None, Some(item)) if !self.group_by.is_empty() => group_id(item, &self.group_by),
+            _ => return,
+        };
+        if !self.collapsed.remove(&group) {
+            self.collapsed.insert(group.clone());
+        }
+        self.selected_group = Some(group);
+        self.fix_selection();
+    }
+
+    /// Indices of the items in a group
+    fn group_members(&self, id: &str) -> Vec<usize> {
+        if id == SNOOZED {
+            return (self.snoozed_from..self.inbox.items.len()).collect();
+        }
+        self.inbox
+            .items
+            .iter()
+            .enumerate()
+            .take(self.snoozed_from)
+            .filter(|(_, item)| {
+                (0..self.group_by.len()).any(|level| group_id(item, &self.group_by[..=level]) == id)
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Ask before deleting every item in the selected group
+    fn confirm_delete_group(&mut self) {
+        let Some(id) = self.selected_group.clone() else {
+            return;
+        };
+        let items = self.group_members(&id);
+        let prompt = format!("Delete {} item(s) in {}? (y/n)", items.len(), id);
+        self.confirm = Some(Confirm::Delete { items, prompt });
+    }
+
+    /// Ask before deleting the selected item (used for Waiting items, which
+    /// still need attention)
+    fn confirm_delete_selected(&mut self) {
+        let Some(idx) = self.selected_item.filter(|_| self.selected().is_some()) else {
+            return;
+        };
+        let prompt = match self.inbox.items[idx].pane_id() {
+            Some(pane) => format!("Delete waiting item in pane {}? (y/n)", pane),
+            None => "Delete waiting item? (y/n)".to_string(),
+        };
+        self.confirm = Some(Confirm::Delete {
+            items: vec![idx],
+            prompt,
         });
     }
 
-    fn previous(&mut self) {
-        if self.inbox.is_empty() {
+    /// Ask before deleting the marked items
+    fn confirm_delete_marked(&mut self) {
+        let items = self.marked_items();
+        let prompt = format!("Delete {} marked item(s)? (y/n)", items.len());
+        self.confirm = Some(Confirm::Delete { items, prompt });
+    }
+
+    /// Carry out a confirmed action, returning removed items
+    fn run_confirmed(&mut self, confirm: Confirm) -> Vec<InboxItem> {
+        match confirm {
+            Confirm::Delete { items, .. } => {
+                let removed = self.delete_items(items);
+                self.message = Some(format!("Deleted {} item(s)", removed.len()));
+                self.marks.clear();
+                self.visual_anchor = None;
+                removed
+            }
+        }
+    }
+
+    /// Items marked for a bulk action: marks plus the visual range
+    fn marked_items(&self) -> Vec<usize> {
+        let mut marked = self.marks.clone();
+        if let Some(anchor) = self.visual_anchor {
+            let rows = self.visual_rows();
+            let start = rows.iter().position(|r| *r == Row::Item(anchor));
+            if let (Some(start), Some(end)) = (start, self.cursor(&rows)) {
+                let (lo, hi) = (start.min(end), start.max(end));
+                marked.extend(rows[lo..=hi].iter().filter_map(|r| match r {
+                    Row::Item(i) => Some(*i),
+                    Row::Header { .. } => None,
+                }));
+            }
+        }
+        marked.into_iter().collect()
+    }
+
+    /// Mark or unmark the selected item
+    fn toggle_mark(&mut self) {
+        if let (None, Some(i)) = (&self.selected_group, self.selected_item) {
+            if !self.marks.remove(&i) {
+                self.marks.insert(i);
+            }
+        }
+    }
+
+    /// Start visual mode at the cursor, or end it keeping the range marked
+    fn toggle_visual(&mut self) {
+        if self.visual_anchor.is_some() {
+            self.marks = self.marked_items().into_iter().collect();
+            self.visual_anchor = None;
+        } else if self.selected_group.is_none() {
+            self.visual_anchor = self.selected_item;
+        }
+    }
+
+    /// Flip Waiting/Working on the marked items (or the selected one),
+    /// returning the new versions
+    fn toggle_status(&mut self) -> Vec<InboxItem> {
+        let now = crate::time::now().to_string();
+        let changed = self.change_marked("status change", |item| {
+            item.status = item.status.toggled();
+            item.attrs.insert("since".to_string(), now.clone());
+        });
+        self.message = Some(format!("Changed status of {} item(s)", changed.len()));
+        changed
+    }
+
+    /// Snooze the marked items (or the selected one) for `secs`, or wake
+    /// them with `None`, returning the new versions
+    fn snooze(&mut self, secs: Option<u64>) -> Vec<InboxItem> {
+        let now = crate::time::now();
+        let action = if secs.is_some() { "snooze" } else { "wake" };
+        let changed = self.change_marked(action, |item| match secs {
+            Some(secs) => {
+                let until = (now + secs).to_string();
+                item.attrs.insert("snooze_until".to_string(), until);
+            }
+            None => {
+                item.attrs.remove("snooze_until");
+            }
+        });
+        self.message = Some(match secs {
+            Some(secs) => format!(
+                "Snoozed {} item(s) for {}",
+                changed.len(),
+                format_duration(secs)
+            ),
+            None => format!("Woke {} item(s)", changed.len()),
+        });
+        changed
+    }
+
+    /// Change and save the marked items (or the selected one), recording
+    /// `action` for undo. Returns the new versions.
+    fn change_marked(&mut self, action: &str, change: impl Fn(&mut InboxItem)) -> Vec<InboxItem> {
+        let mut marked = self.marked_items();
+        if marked.is_empty() {
+            match self.selected().and(self.selected_item) {
+                Some(idx) => marked.push(idx),
+                None => return Vec::new(),
+            }
+        }
+        let label = format!("{} of {} item(s)", action, marked.len());
+        let mut changed = Vec::new();
+        let mut touched = Vec::new();
+        for (path, group) in self.by_path(&marked) {
+            let mut store = Store::open(&path);
+            let Ok(before) = store.load() else {
+                continue;
+            };
+            let mut changes = Vec::new();
+            for idx in group {
+                // Change the current version: an agent may have updated it
+                // since it was shown. Items removed meanwhile are skipped.
+                let shown = self.stored(&self.inbox.items[idx]);
+                let Some(current) = before.find(&shown) else {
+                    continue;
+                };
+                let mut item = before.items[current].clone();
+                change(&mut item);
+                if let Ok(replaced) = store.add(item.clone()) {
+                    changes.extend(undo::ItemChange::added(replaced, item.clone()));
+                    changed.push(item);
+                }
+            }
+            if !changes.is_empty() {
+                undo::record(&path, &label, changes);
+                touched.push(path);
+            }
+        }
+        self.recorded(touched);
+        self.marks.clear();
+        self.visual_anchor = None;
+        self.reload();
+        changed
+    }
+
+    /// Index of the selected item, unless a header is selected
+    fn editable(&self) -> Option<usize> {
+        self.selected().and(self.selected_item)
+    }
+
+    /// Start editing the selected item's markdown line in the status line
+    fn start_edit(&mut self) {
+        let Some(idx) = self.editable() else {
             return;
+        };
+        let snippet = edit_snippet(self.stored(&self.inbox.items[idx]));
+        let line = snippet.lines().last().unwrap_or_default().to_string();
+        self.edit = Some(Edit {
+            item: idx,
+            input: LineInput::new(line),
+        });
+    }
+
+    /// Edit the selected item as a markdown snippet in the external editor,
+    /// returning the new version if it was saved
+    fn edit_externally(&mut self, events: &mut impl EventSource) -> Option<InboxItem> {
+        let idx = self.editable()?;
+        let Some(editor) = self.editor.clone() else {
+            self.message = Some("Set $EDITOR to edit with E (or use e)".to_string());
+            return None;
+        };
+        let tmp = std::env::temp_dir().join(format!("tael-edit-{}.md", std::process::id()));
+        let edited = (|| {
+            std::fs::write(&tmp, edit_snippet(self.stored(&self.inbox.items[idx])))?;
+            let mut words = shell_words::split(&editor)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            if words.is_empty() {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, "empty $EDITOR"));
+            }
+            let status = events.suspend(Command::new(words.remove(0)).args(words).arg(&tmp))?;
+            if !status.success() {
+                return Err(io::Error::other(format!("editor exited with {}", status)));
+            }
+            std::fs::read_to_string(&tmp)
+        })();
+        let _ = std::fs::remove_file(&tmp);
+        match edited {
+            Ok(text) => self.save_edit(idx, &text),
+            Err(e) => {
+                self.message = Some(format!("Edit discarded: {}", e));
+                None
+            }
+        }
+    }
+
+    /// Replace an item with its edited markdown, returning the new version
+    /// if anything changed
+    fn save_edit(&mut self, idx: usize, text: &str) -> Option<InboxItem> {
+        let original = self.stored(&self.inbox.items[idx]);
+        let mut parsed = crate::parse::parse(text);
+        if parsed.items.len() != 1 {
+            self.message = Some("Edit discarded: expected exactly one item".to_string());
+            return None;
         }
-        self.selected_item = Some(match self.selected_item {
-            Some(i) => i.saturating_sub(1),
-            None => 0,
+        let mut item = parsed.items.remove(0);
+        if let Some(id) = original.id() {
+            item.attrs
+                .entry("id".to_string())
+                .or_insert_with(|| id.to_string());
+        }
+        if item.attrs == original.attrs && item.status == original.status {
+            return None;
+        }
+        if item.status != original.status {
+            item.attrs
+                .insert("since".to_string(), crate::time::now().to_string());
+        }
+
+        let path = self.item_path(&self.inbox.items[idx]);
+        let mut store = Store::open(&path);
+        let saved = (|| {
+            let mut changes = Vec::new();
+            // A changed id means a different item in the store
+            if let Some(id) = original.id().filter(|&id| item.id() != Some(id)) {
+                changes.extend(store.remove_id(id)?.map(undo::ItemChange::removed));
+            }
+            let replaced = store.add(item.clone())?;
+            changes.extend(undo::ItemChange::added(replaced, item.clone()));
+            io::Result::Ok(changes)
+        })();
+        match saved {
+            Ok(changes) => {
+                undo::record(&path, "edit of 1 item(s)", changes);
+                self.recorded(vec![path]);
+                self.message = Some("Saved".to_string());
+                self.reload();
+                Some(item)
+            }
+            Err(e) => {
+                self.message = Some(format!("Edit failed: {}", e));
+                None
+            }
+        }
+    }
+
+    /// Add (or, by pane, replace) an item in this session's inbox and select
+    /// it. Returns the hook event to fire and the stored item, or None if
+    /// saving failed.
+    fn add_item(&mut self, mut item: InboxItem) -> Option<(HookEvent, InboxItem)> {
+        let path = self.path.clone();
+        let mut store = Store::open(&path);
+        let added = store.load().and_then(|before| {
+            let id = match before.find(&item).and_then(|idx| before.items[idx].id()) {
+                Some(id) => id.to_string(),
+                None => before.new_id(),
+            };
+            item.attrs.insert("id".to_string(), id);
+            store.add(item.clone())
         });
+        let replaced = match added {
+            Ok(added) => added,
+            Err(e) => {
+                self.message = Some(format!("Add failed: {}", e));
+                return None;
+            }
+        };
+        let changes = undo::ItemChange::added(replaced.clone(), item.clone());
+        undo::record(&path, "add of 1 item(s)", changes);
+        self.recorded(vec![path.clone()]);
+        self.reload();
+        if let Some(idx) = self
+            .inbox
+            .items
+            .iter()
+            .position(|i| i.id() == item.id() && self.item_path(i) == path)
+        {
+            self.select_row(&Row::Item(idx));
+        }
+        self.message = Some("Added".to_string());
+        let event = match replaced {
+            Some(_) => HookEvent::Update,
+            None => HookEvent::Add,
+        };
+        Some((event, item))
+    }
+
+    /// Select the next marked item with a pane, cycling; false if none
+    fn cycle_marked(&mut self) -> bool {
+        let marked: Vec<usize> = self
+            .marked_items()
+            .into_iter()
+            .filter(|&i| self.inbox.items[i].pane_id().is_some())
+            .collect();
+        if marked.is_empty() {
+            self.message = Some("No items marked (Space or v to mark)".to_string());
+            return false;
+        }
+        let pos = self.cycle % marked.len();
+        self.cycle = pos + 1;
+        self.select_row(&Row::Item(marked[pos]));
+        self.message = Some(format!("Focused {}/{}", pos + 1, marked.len()));
+        true
+    }
+
+    /// Remember which files an action recorded undo snapshots in
+    fn recorded(&mut self, paths: Vec<PathBuf>) {
+        if !paths.is_empty() {
+            self.undo_paths.push(paths);
+            self.redo_paths.clear();
+        }
+    }
+
+    /// Undo the last action, returning the changes made. Falls back to the
+    /// inbox file's own stack, so CLI actions can be undone too.
+    fn undo(&mut self) -> Vec<Change> {
+        self.step(true)
+    }
+
+    /// Redo the last undone action
+    fn redo(&mut self) -> Vec<Change> {
+        self.step(false)
+    }
+
+    fn step(&mut self, undo: bool) -> Vec<Change> {
+        let (from, to) = if undo {
+            (&mut self.undo_paths, &mut self.redo_paths)
+        } else {
+            (&mut self.redo_paths, &mut self.undo_paths)
+        };
+        let paths = from.pop().unwrap_or_else(|| vec![self.path.clone()]);
+
+        let mut label = None;
+        let mut changes = Vec::new();
+        let mut skipped = 0;
+        for path in &paths {
+            let step = if undo {
+                undo::undo(path)
+            } else {
+                undo::redo(path)
+            };
+            if let Ok(Some(step)) = step {
+                label.get_or_insert(step.label);
+                skipped += step.skipped;
+                changes.extend(step.changes);
+            }
+        }
+
+        let (verb, nothing) = if undo {
+            ("Undid", "Nothing to undo")
+        } else {
+            ("Redid", "Nothing to redo")
+        };
+        self.message = Some(match label {
+            Some(label) => {
+                to.push(paths);
+                format!("{} {}{}", verb, label, undo::skipped_note(skipped))
+            }
+            None => nothing.to_string(),
+        });
+        self.reload();
+        changes
+    }
+
+    /// An item as stored in its inbox file (without the "session" tag added
+    /// when showing all sessions)
+    fn stored(&self, item: &InboxItem) -> InboxItem {
+        let mut item = item.clone();
+        if self.sessions_dir.is_some() {
+            item.attrs.remove("session");
+        }
+        item
+    }
+
+    /// The list row under a screen position
+    fn row_at(&self, column: u16, row: u16) -> Option<Row> {
+        if !self.list_area.contains(Position::new(column, row)) {
+            return None;
+        }
+        let visual = (row - self.list_area.y) as usize + self.list_state.offset();
+        self.rows.get(visual).cloned()
+    }
+
+    /// Select a clicked item, returning true on a double-click
+    fn click(&mut self, idx: usize) -> bool {
+        let now = Instant::now();
+        let double = matches!(self.last_click, Some((at, i)) if i == idx && now.duration_since(at) < DOUBLE_CLICK);
+        self.last_click = if double { None } else { Some((now, idx)) };
+        self.select_row(&Row::Item(idx));
+        double
+    }
+
+    /// The selected item (None when the cursor is on a group header)
+    fn selected(&self) -> Option<&InboxItem> {
+        if self.selected_group.is_some() {
+            return None;
+        }
+        self.selected_item.and_then(|i| self.inbox.items.get(i))
+    }
+
+    /// Inbox file an item belongs to (its session's file when showing all sessions)
+    fn item_path(&self, item: &InboxItem) -> PathBuf {
+        match (&self.sessions_dir, item.get("session")) {
+            (Some(dir), Some(session)) => dir.join(format!("{}.md", session)),
+            _ => self.path.clone(),
+        }
     }
 
-    fn selected_pane_id(&self) -> Option<u32> {
-        self.selected_item
-            .and_then(|i| self.inbox.items.get(i))
-            .and_then(|item| item.pane_id())
+    /// Delete the selected item, returning it if one was removed
+    fn delete_selected(&mut self) -> Option<InboxItem> {
+        self.selected()?;
+        let idx = self.selected_item?;
+        self.delete_items(vec![idx]).pop()
     }
 
-    fn delete_selected(&mut self) {
-        if let Some(pane_id) = self.selected_pane_id() {
-            self.inbox.remove(pane_id);
-            let _ = crate::file::save(&self.path, &self.inbox);
-            // Adjust selection
-            if let Some(i) = self.selected_item {
-                if i >= self.inbox.items.len() && !self.inbox.is_empty() {
-                    self.selected_item = Some(self.inbox.items.len() - 1);
-                } else if self.inbox.is_empty() {
-                    self.selected_item = None;
+    /// Group item indices by inbox file. Items without an id can't be
+    /// addressed in the store and are left out.
+    fn by_path(&self, indices: &[usize]) -> Vec<(PathBuf, Vec<usize>)> {
+        let mut by_path: Vec<(PathBuf, Vec<usize>)> = Vec::new();
+        for &idx in indices {
+            let Some(item) = self.inbox.items.get(idx).filter(|i| i.id().is_some()) else {
+                continue;
+            };
+            let path = self.item_path(item);
+            match by_path.iter_mut().find(|(p, _)| *p == path) {
+                Some((_, group)) => group.push(idx),
+                None => by_path.push((path, vec![idx])),
+            }
+        }
+        by_path
+    }
+
+    /// Delete items by index, returning the ones removed
+    fn delete_items(&mut self, indices: Vec<usize>) -> Vec<InboxItem> {
+        let label = format!("delete of {} item(s)", indices.len());
+        let mut removed = Vec::new();
+        let mut touched = Vec::new();
+        let mut gone = BTreeSet::new();
+        // Ids are only unique within a session, so remove per file
+        for (path, group) in self.by_path(&indices) {
+            let ids: Vec<String> = group
+                .iter()
+                .filter_map(|&i| self.inbox.items[i].id().map(String::from))
+                .collect();
+            if let Ok(items) = Store::open(&path).remove_many(&ids) {
+                if !items.is_empty() {
+                    undo::record_removed(&path, &label, &items);
+                    touched.push(path);
                 }
+                removed.extend(items);
             }
+            gone.extend(group);
+        }
+        self.recorded(touched);
+
+        // Drop from the in-memory inbox by index, shifting marks to match
+        for &idx in gone.iter().rev() {
+            self.inbox.items.remove(idx);
         }
+        self.marks = self
+            .marks
+            .iter()
+            .filter(|i| !gone.contains(i))
+            .map(|&i| i - gone.range(..i).count())
+            .collect();
+        if let Some(i) = self.selected_item {
+            // Keep the cursor on the item that moved into the deleted slot
+            self.selected_item = Some(i - gone.range(..i).count());
+        }
+        self.fix_selection();
+        removed
+    }
+
+    /// Mark the selected item seen, if it isn't already
+    fn mark_seen(&mut self) {
+        let Some(idx) = self.editable() else {
+            return;
+        };
+        if self.inbox.items[idx].is_seen() {
+            return;
+        }
+        let item = &self.inbox.items[idx];
+        let Some(id) = item.id() else {
+            return;
+        };
+        // Only the message on screen has been seen: an agent may have updated
+        // the item since it was shown
+        let mut store = Store::open(&self.item_path(item));
+        if let Ok(Some(_)) = store.mark_seen(id, item.msg()) {
+            self.inbox.items[idx]
+                .attrs
+                .insert("seen".to_string(), "true".to_string());
+        }
+    }
+
+    /// Move items whose snooze ran out back among the others
+    fn wake_expired(&mut self, now: u64) {
+        if self.inbox.items[self.snoozed_from.min(self.inbox.items.len())..]
+            .iter()
+            .any(|i| !i.is_snoozed(now))
+        {
+            self.arrange(self.selected().cloned());
+            self.fix_selection();
+        }
+    }
+
+    /// Look for urgent items waiting too long (fresh from disk, since the
+    /// shown inbox may be stale): sends escalation notifications and sets the
+    /// status line alert. Returns whether a newly overdue item rings the bell.
+    fn escalate(&mut self, config: &Config, now: u64) -> bool {
+        let Ok(inbox) = load_inbox(&self.path, self.sessions_dir.as_deref()) else {
+            return false;
+        };
+        let settings = &config.escalate;
+        let _ = escalate::check(settings, &config.notify, &self.path, &inbox, now);
+
+        let overdue = escalate::overdue(settings, &inbox, now);
+        self.alert = (settings.has(Action::Flash) && !overdue.is_empty()).then(|| {
+            format!(
+                "{} urgent item(s) waiting over {}",
+                overdue.len(),
+                format_duration(settings.after)
+            )
+        });
+        let stints: HashSet<String> = overdue.into_iter().map(escalate::stint).collect();
+        let ring = stints.iter().any(|s| !self.rung.contains(s));
+        self.rung = stints;
+        ring && settings.has(Action::Bell)
     }
 
     fn reload(&mut self) {
-        if let Ok(inbox) = crate::file::load(&self.path) {
+        if let Ok(inbox) = load_inbox(&self.path, self.sessions_dir.as_deref()) {
+            let selected = self
+                .selected_item
+                .and_then(|i| self.inbox.items.get(i))
+                .cloned();
             self.inbox = inbox;
-            if let Some(i) = self.selected_item {
-                if i >= self.inbox.items.len() {
-                    self.selected_item = if self.inbox.is_empty() {
-                        None
-                    } else {
-                        Some(self.inbox.items.len() - 1)
-                    };
-                }
+            self.arrange(selected);
+            self.fix_selection();
+        }
+    }
+
+    /// Use a new display order, cycled with `o` along with the presets
+    fn set_sort(&mut self, sort: Sort) {
+        self.sorts = vec![sort.clone()];
+        for preset in SORT_PRESETS {
+            let preset: Sort = preset.parse().expect("valid sort preset");
+            if !self.sorts.contains(&preset) {
+                self.sorts.push(preset);
             }
         }
+        self.sort = sort;
+        self.arrange(self.selected().cloned());
+        self.fix_selection();
+    }
+
+    /// Switch to the next sort order
+    fn cycle_sort(&mut self) {
+        let pos = self.sorts.iter().position(|s| *s == self.sort).unwrap_or(0);
+        self.sort = self.sorts[(pos + 1) % self.sorts.len()].clone();
+        self.arrange(self.selected().cloned());
+        self.message = Some(format!("Sort: {}", self.sort));
+    }
+
+    /// Put the items in display order, keeping the cursor on `selected`.
+    /// Marks are by index, so they're dropped if anything moved.
+    fn arrange(&mut self, selected: Option<InboxItem>) {
+        let before: Vec<Option<String>> = self
+            .inbox
+            .items
+            .iter()
+            .map(|i| i.id().map(String::from))
+            .collect();
+        self.snoozed_from = arrange(
+            &mut self.inbox,
+            &self.sort,
+            &self.group_by,
+            crate::time::now(),
+        );
+        let moved = self
+            .inbox
+            .items
+            .iter()
+            .map(|i| i.id().map(String::from))
+            .ne(before);
+        if moved {
+            self.marks.clear();
+            self.visual_anchor = None;
+        }
+        if let Some(selected) = selected {
+            self.selected_item = self
+                .inbox
+                .items
+                .iter()
+                .position(|i| match selected.id() {
+                    Some(id) => i.id() == Some(id) && i.get("session") == selected.get("session"),
+                    None => i.attrs == selected.attrs,
+                })
+                .or(self.selected_item);
+        }
     }
 }
 
 fn draw(frame: &mut Frame, app: &mut App) {
     let area = frame.area();
 
-    // Split area: hints (1 line) + separator (1 line) + content
+    // Status line only while there is something to say
+    let status = match &app.confirm {
+        Some(Confirm::Delete { prompt, .. }) => Some(prompt.clone()),
+        None => app.message.clone().or_else(|| {
+            let marked = app.marked_items().len();
+            match (app.visual_anchor, marked) {
+                (Some(_), n) => Some(format!("-- VISUAL -- {} selected", n)),
+                (None, 0) => None,
+                (None, n) => Some(format!("{} marked", n)),
+            }
+        }),
+    };
+
+    // Overdue urgent items flash the status line when nothing else is shown
+    let alerting = status.is_none() && app.alert.is_some();
+    let status = status.or_else(|| app.alert.clone());
+
+    // Split area: hints (1 line) + separator (1 line) + content + status
     let chunks = Layout::vertical([
         Constraint::Length(1), // hints
         Constraint::Length(1), // separator line
         Constraint::Min(1),    // content
+        Constraint::Length(match app.add {
+            Some(_) => ADD_FORM_HEIGHT,
+            None => (status.is_some() || app.edit.is_some()) as u16,
+        }),
     ])
     .split(area);
 
-    // Hints line
+    // Hints line, with the unseen count on the right
+    let unseen = app.inbox.items[..app.snoozed_from.min(app.inbox.items.len())]
+        .iter()
+        .filter(|i| !i.is_seen())
+        .count();
+    let unseen = (unseen > 0).then(|| format!("  {} {} unseen", UNSEEN, unseen));
+    let header = Layout::horizontal([
+        Constraint::Min(0),
+        Constraint::Length(unseen.as_ref().map_or(0, |u| u.chars().count() as u16)),
+    ])
+    .split(chunks[0]);
     let hints = Line::from(vec![
+        Span::styled("?", Style::default().fg(Color::Yellow)),
+        Span::raw(":help  "),
         Span::styled("j/k", Style::default().fg(Color::Yellow)),
         Span::raw(":nav  "),
         Span::styled("Enter", Style::default().fg(Color::Yellow)),
@@ -190,68 +1455,305 @@ fn draw(frame: &mut Frame, app: &mut App) {
     ]);
     frame.render_widget(
         Paragraph::new(hints).style(Style::default().fg(Color::DarkGray)),
-        chunks[0],
+        header[0],
+    );
+    if let Some(unseen) = unseen {
+        frame.render_widget(
+            Paragraph::new(unseen).style(Style::default().add_modifier(Modifier::BOLD)),
+            header[1],
+        );
+    }
+
+    // Separator
+    let sep = "─".repeat(chunks[1].width as usize);
+    frame.render_widget(
+        Paragraph::new(sep).style(Style::default().fg(Color::DarkGray)),
+        chunks[1],
+    );
+
+    // Content area, with the details panel below the list while open
+    let details = app
+        .selected()
+        .filter(|_| app.details)
+        .map(|item| crate::render::details(item, crate::time::now()));
+    let content = match &details {
+        Some(text) => {
+            let height = (text.lines().count() as u16 + 1).min(chunks[2].height / 2);
+            let parts =
+                Layout::vertical([Constraint::Min(1), Constraint::Length(height)]).split(chunks[2]);
+            let sep = "─".repeat(parts[1].width as usize);
+            let lines: Vec<Line> =
+                std::iter::once(Line::styled(sep, Style::default().fg(Color::DarkGray)))
+                    .chain(text.lines().map(|l| Line::raw(l.to_string())))
+                    .collect();
+            frame.render_widget(Paragraph::new(lines), parts[1]);
+            parts[0]
+        }
+        None => chunks[2],
+    };
+    app.list_area = content;
+    app.rows.clear();
+    if app.inbox.is_empty() {
+        let empty = Paragraph::new("  (no items)").style(
+            Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::ITALIC),
+        );
+        frame.render_widget(empty, content);
+    } else {
+        // Build list items with section headers inline
+        let marked = app.marked_items();
+        let (items, rows) = build_list_items(
+            &app.inbox,
+            &app.group_by,
+            &app.collapsed,
+            app.snoozed_from,
+            &marked,
+        );
+        app.list_state.select(app.cursor(&rows));
+        app.rows = rows;
+
+        let list = List::new(items)
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("▶ ");
+        frame.render_stateful_widget(list, content, &mut app.list_state);
+    }
+
+    if app.help {
+        draw_help(frame, chunks[2]);
+    }
+
+    let style = Style::default().fg(Color::Yellow);
+    if let Some(form) = &app.add {
+        draw_add_form(frame, chunks[3], form);
+    } else if let Some(edit) = &app.edit {
+        draw_input(frame, chunks[3], "edit: ", &edit.input, style, true);
+    } else if let Some(status) = status {
+        let style = if alerting {
+            Style::default()
+                .fg(Color::Red)
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+        } else {
+            style
+        };
+        frame.render_widget(Paragraph::new(status).style(style), chunks[3]);
+    }
+}
+
+/// Keys listed by the `?` overlay
+const HELP: &[(&str, &str)] = &[
+    ("j/k", "move"),
+    ("Enter", "focus item / fold group"),
+    ("Space", "mark item / fold group"),
+    ("v", "mark a range"),
+    ("Tab", "visit marked items"),
+    ("d", "delete"),
+    ("s", "toggle waiting/working"),
+    ("e / E", "edit message / in $EDITOR"),
+    ("a", "add item"),
+    ("u / C-r", "undo / redo"),
+    ("zz / z1-9", "snooze 30m / hours"),
+    ("zw", "wake"),
+    ("za", "fold group"),
+    ("i", "details"),
+    ("o", "cycle sort"),
+    ("p", "pin pane (Zellij)"),
+    ("r", "reload"),
+    ("q", "quit"),
+];
+
+/// Draw the key help over the list
+fn draw_help(frame: &mut Frame, area: Rect) {
+    let width = HELP.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+    let lines: Vec<Line> = HELP
+        .iter()
+        .map(|(keys, action)| {
+            Line::from(vec![
+                Span::styled(
+                    format!(" {:<width$}  ", keys, width = width),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::raw(*action),
+            ])
+        })
+        .collect();
+    frame.render_widget(Clear, area);
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+/// Draw a prompt and an input on one line, scrolled so the cursor stays
+/// visible; the terminal cursor is placed there if `focused`
+fn draw_input(
+    frame: &mut Frame,
+    area: Rect,
+    prompt: &str,
+    input: &LineInput,
+    style: Style,
+    focused: bool,
+) {
+    let text = format!("{}{}", prompt, input.text);
+    let cursor = prompt.chars().count() + input.cursor;
+    let scroll = cursor.saturating_sub(area.width.saturating_sub(1) as usize);
+    let visible: String = text.chars().skip(scroll).collect();
+    frame.render_widget(Paragraph::new(visible).style(style), area);
+    if focused {
+        frame.set_cursor_position(Position::new(area.x + (cursor - scroll) as u16, area.y));
+    }
+}
+
+/// Draw the new-item form: a help (or error) line and one line per field
+fn draw_add_form(frame: &mut Frame, area: Rect, form: &AddForm) {
+    let rows = Layout::vertical([Constraint::Length(1); ADD_FORM_HEIGHT as usize]).split(area);
+    let help = form
+        .error
+        .clone()
+        .unwrap_or_else(|| "New item: Tab next field, Enter add, Esc cancel".to_string());
+    frame.render_widget(
+        Paragraph::new(help).style(Style::default().fg(Color::Yellow)),
+        rows[0],
+    );
+    let style = |field| {
+        let color = if form.field == field {
+            Color::Green
+        } else {
+            Color::DarkGray
+        };
+        Style::default().fg(color)
+    };
+    draw_input(
+        frame,
+        rows[1],
+        "   msg: ",
+        &form.msg,
+        style(AddField::Msg),
+        form.field == AddField::Msg,
     );
-
-    // Separator
-    let sep = "─".repeat(chunks[1].width as usize);
+    let status = format!("status: {} (Space toggles)", form.status.as_str());
     frame.render_widget(
-        Paragraph::new(sep).style(Style::default().fg(Color::DarkGray)),
-        chunks[1],
+        Paragraph::new(status).style(style(AddField::Status)),
+        rows[2],
     );
+    draw_input(
+        frame,
+        rows[3],
+        " attrs: ",
+        &form.attrs,
+        style(AddField::Attrs),
+        form.field == AddField::Attrs,
+    );
+}
 
-    // Content area
-    if app.inbox.is_empty() {
-        let empty = Paragraph::new("  (no items)").style(
-            Style::default()
-                .fg(Color::DarkGray)
-                .add_modifier(Modifier::ITALIC),
-        );
-        frame.render_widget(empty, chunks[2]);
-    } else {
-        // Build list items with section headers inline, get mapping
-        let (items, item_to_visual) = build_list_items(&app.inbox, &app.group_by);
+/// What a row of the visual list shows
+#[derive(Debug, Clone, PartialEq)]
+enum Row {
+    /// A group header: its id (see `group_id`), depth and "value (count)" label
+    Header {
+        id: String,
+        level: usize,
+        label: String,
+    },
+    /// An item, by index into inbox.items
+    Item(usize),
+}
+
+/// Marker for items not looked at since their message last changed
+const UNSEEN: &str = "•";
+
+/// Header id (and label) of the section snoozed items are listed in
+const SNOOZED: &str = "Snoozed";
 
-        // Set visual index from selected item
-        if let Some(item_idx) = app.selected_item {
-            if let Some(&visual_idx) = item_to_visual.get(item_idx) {
-                app.list_state.select(Some(visual_idx));
+/// Put items in display order: by `sort`, then with each group's items
+/// together (groups in the order of their first item), then snoozed items
+/// last. Returns the index of the first snoozed item.
+pub fn arrange(inbox: &mut Inbox, sort: &Sort, group_by: &[String], now: u64) -> usize {
+    sort.apply(&mut inbox.items, now);
+    if !group_by.is_empty() {
+        let mut first: HashMap<String, usize> = HashMap::new();
+        for (i, item) in inbox.items.iter().enumerate() {
+            for level in 0..group_by.len() {
+                first
+                    .entry(group_id(item, &group_by[..=level]))
+                    .or_insert(i);
             }
         }
-
-        let list = List::new(items)
-            .highlight_style(
-                Style::default()
-                    .fg(Color::Green)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .highlight_symbol("▶ ");
-        frame.render_stateful_widget(list, chunks[2], &mut app.list_state);
+        inbox.items.sort_by_cached_key(|item| {
+            (0..group_by.len())
+                .map(|level| first[&group_id(item, &group_by[..=level])])
+                .collect::<Vec<_>>()
+        });
     }
+    inbox.items.sort_by_key(|item| item.is_snoozed(now));
+    inbox
+        .items
+        .iter()
+        .take_while(|i| !i.is_snoozed(now))
+        .count()
+}
+
+/// Identifies an item's group at the depth of `group_by` (values joined by "/")
+fn group_id(item: &InboxItem, group_by: &[String]) -> String {
+    group_by
+        .iter()
+        .map(|key| get_group_value(item, key))
+        .collect::<Vec<_>>()
+        .join("/")
 }
 
-/// Returns (visual list items, mapping from inbox item index to visual index)
-fn build_list_items(inbox: &Inbox, group_by: &[String]) -> (Vec<ListItem<'static>>, Vec<usize>) {
-    let mut items = Vec::new();
-    let mut item_to_visual = Vec::new(); // item_to_visual[inbox_idx] = visual_idx
+/// Rows of the visual list: group headers inline before their items, and
+/// items from `snoozed_from` on under a "Snoozed" header at the end.
+/// Items and deeper headers inside a collapsed group are left out.
+fn visual_rows(
+    inbox: &Inbox,
+    group_by: &[String],
+    collapsed: &HashSet<String>,
+    snoozed_from: usize,
+) -> Vec<Row> {
+    let snoozed_from = snoozed_from.min(inbox.items.len());
+    let mut rows = grouped_rows(&inbox.items[..snoozed_from], group_by, collapsed);
+    if snoozed_from < inbox.items.len() {
+        rows.push(Row::Header {
+            id: SNOOZED.to_string(),
+            level: 0,
+            label: format!("{} ({})", SNOOZED, inbox.items.len() - snoozed_from),
+        });
+        if !collapsed.contains(SNOOZED) {
+            rows.extend((snoozed_from..inbox.items.len()).map(Row::Item));
+        }
+    }
+    rows
+}
 
+/// Rows for `items` (the start of inbox.items) grouped by `group_by`
+fn grouped_rows(items: &[InboxItem], group_by: &[String], collapsed: &HashSet<String>) -> Vec<Row> {
     // If no grouping specified, render flat list
     if group_by.is_empty() {
-        for item in inbox.items.iter() {
-            item_to_visual.push(items.len());
-            let item_line = Line::from(format!("[ ] {}", item.msg()));
-            items.push(ListItem::new(item_line));
+        return (0..items.len()).map(Row::Item).collect();
+    }
+
+    // Items per group, for the header counts
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for item in items {
+        for level in 0..group_by.len() {
+            *counts
+                .entry(group_id(item, &group_by[..=level]))
+                .or_default() += 1;
         }
-        return (items, item_to_visual);
     }
 
+    let mut rows = Vec::new();
     // Track current group values for each level
     let mut current_groups: Vec<Option<String>> = vec![None; group_by.len()];
 
-    for item in inbox.items.iter() {
+    for (idx, item) in items.iter().enumerate() {
         // Check each grouping level and emit headers as needed
+        let mut hidden = false;
         for (level, key) in group_by.iter().enumerate() {
             let value = get_group_value(item, key);
+            let id = group_id(item, &group_by[..=level]);
 
             if current_groups[level].as_ref() != Some(&value) {
                 // Reset all deeper levels when this level changes
@@ -260,33 +1762,124 @@ fn build_list_items(inbox: &Inbox, group_by: &[String]) -> (Vec<ListItem<'static
                 }
                 current_groups[level] = Some(value.clone());
 
+                // Headers inside a collapsed group are hidden too
+                if !hidden {
+                    let label = format!("{} ({})", value, counts[&id]);
+                    rows.push(Row::Header {
+                        id: id.clone(),
+                        level,
+                        label,
+                    });
+                }
+            }
+            hidden |= collapsed.contains(&id);
+        }
+
+        if !hidden {
+            rows.push(Row::Item(idx));
+        }
+    }
+
+    rows
+}
+
+/// Returns (visual list items, what each visual row shows)
+fn build_list_items(
+    inbox: &Inbox,
+    group_by: &[String],
+    collapsed: &HashSet<String>,
+    snoozed_from: usize,
+    marked: &[usize],
+) -> (Vec<ListItem<'static>>, Vec<Row>) {
+    let now = crate::time::now();
+    let rows = visual_rows(inbox, group_by, collapsed, snoozed_from);
+    let items = rows
+        .iter()
+        .map(|row| match row {
+            Row::Header { id, level, label } => {
                 // Emit header with appropriate indentation and color
-                let indent = "  ".repeat(level);
+                let indent = "  ".repeat(*level);
                 let (color, modifier) = match level {
+                    _ if id == SNOOZED => (Color::DarkGray, Modifier::BOLD),
                     0 => (Color::Yellow, Modifier::BOLD),
                     1 => (Color::Magenta, Modifier::empty()),
                     _ => (Color::Cyan, Modifier::empty()),
                 };
-                let header_line = Line::from(Span::styled(
-                    format!("{}{}", indent, value),
+                let marker = if collapsed.contains(id) { "▸ " } else { "" };
+                ListItem::new(Line::from(Span::styled(
+                    format!("{}{}{}", indent, marker, label),
                     Style::default().fg(color).add_modifier(modifier),
-                ));
-                items.push(ListItem::new(header_line));
+                )))
             }
-        }
+            Row::Item(idx) => {
+                // Item line - indent based on group depth (snoozed items
+                // sit one level under their header)
+                let snoozed = *idx >= snoozed_from;
+                let base_indent = "  ".repeat(if snoozed { 1 } else { group_by.len() });
+                let item = &inbox.items[*idx];
+                let check = match (marked.contains(idx), item.is_seen()) {
+                    (true, _) => "[x]".to_string(),
+                    (false, true) => "[ ]".to_string(),
+                    (false, false) => format!("[{}]", UNSEEN),
+                };
+                let mut style = priority_style(item.priority());
+                if !item.is_seen() {
+                    style = style.add_modifier(Modifier::BOLD);
+                }
+                let mut spans = vec![
+                    Span::raw(format!("{}{} ", base_indent, check)),
+                    Span::styled(item.msg().to_string(), style),
+                ];
+                if item.is_pinned() {
+                    spans.push(Span::styled(
+                        " (pinned)",
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                if let Some(until) = item.snooze_until().filter(|_| snoozed) {
+                    spans.push(Span::styled(
+                        format!(" ({} left)", format_duration(until.saturating_sub(now))),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                ListItem::new(Line::from(spans))
+            }
+        })
+        .collect();
+    (items, rows)
+}
 
-        // Item line - indent based on group depth, record its visual index
-        item_to_visual.push(items.len());
-        let base_indent = "  ".repeat(group_by.len());
-        let item_line = Line::from(format!("{}[ ] {}", base_indent, item.msg()));
-        items.push(ListItem::new(item_line));
+/// Message style for a priority: urgent red, high yellow, low dimmed
+fn priority_style(priority: Priority) -> Style {
+    match priority {
+        Priority::Urgent => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        Priority::High => Style::default().fg(Color::Yellow),
+        Priority::Normal => Style::default(),
+        Priority::Low => Style::default().fg(Color::DarkGray),
+    }
+}
+
+/// ANSI color codes matching `priority_style`
+fn priority_ansi(priority: Priority) -> Option<&'static str> {
+    match priority {
+        Priority::Urgent => Some("1;31"),
+        Priority::High => Some("33"),
+        Priority::Normal => None,
+        Priority::Low => Some("2"),
     }
+}
 
-    (items, item_to_visual)
+/// Message truncated to `max_len` chars, colored by priority if `colors`
+fn list_text(item: &InboxItem, max_len: usize, colors: bool) -> String {
+    let text: String = item.msg().chars().take(max_len).collect();
+    match priority_ansi(item.priority()) {
+        Some(code) if colors => format!("\x1b[{}m{}\x1b[0m", code, text),
+        _ => text,
+    }
 }
 
 /// Get a grouping key value from an item for the given group key
-fn get_group_value(item: &crate::InboxItem, key: &str) -> String {
+fn get_group_value(item: &InboxItem, key: &str) -> String {
     match key {
         "status" => item.status.section_name().to_string(),
         "proj" => match item.branch() {
@@ -297,6 +1890,28 @@ fn get_group_value(item: &crate::InboxItem, key: &str) -> String {
     }
 }
 
+/// Render snoozed items for list output: a "Snoozed (N)" header, then each
+/// item with the time left
+pub fn render_snoozed(items: &Inbox, width: usize, colors: bool, now: u64) -> String {
+    let header = format!("{} ({})", SNOOZED, items.items.len());
+    let mut output = if colors {
+        format!("\x1b[1;2m{}\x1b[0m\n", header)
+    } else {
+        format!("{}\n", header)
+    };
+    for item in &items.items {
+        let left = format!(
+            " ({} left)",
+            format_duration(item.snooze_until().unwrap_or(now).saturating_sub(now))
+        );
+        let prefix = "    [ ] ";
+        let max_len = width.saturating_sub(prefix.len() + left.len());
+        let text = list_text(item, max_len, colors);
+        output.push_str(&format!("{}{}{}\n", prefix, text, left));
+    }
+    output
+}
+
 /// Render inbox for non-interactive list output (respects terminal width)
 pub fn render_list(inbox: &Inbox, width: usize, colors: bool, group_by: &[String]) -> String {
     let mut output = String::new();
@@ -315,7 +1930,7 @@ pub fn render_list(inbox: &Inbox, width: usize, colors: bool, group_by: &[String
         for (idx, item) in inbox.items.iter().enumerate() {
             let prefix = if idx == 0 { "▶ [ ] " } else { "  [ ] " };
             let max_len = width.saturating_sub(prefix.len());
-            let text: String = item.msg().chars().take(max_len).collect();
+            let text = list_text(item, max_len, colors);
             output.push_str(&format!("{}{}\n", prefix, text));
         }
         return output;
@@ -360,7 +1975,7 @@ pub fn render_list(inbox: &Inbox, width: usize, colors: bool, group_by: &[String
             format!("{}  [ ] ", base_indent)
         };
         let max_len = width.saturating_sub(prefix.len());
-        let text: String = item.msg().chars().take(max_len).collect();
+        let text = list_text(item, max_len, colors);
         output.push_str(&format!("{}{}\n", prefix, text));
     }
 
@@ -441,4 +2056,761 @@ mod tests {
         // Status header should not be present with only proj grouping
         assert!(!output.contains("Waiting for Input"));
     }
+
+    fn test_config() -> Config {
+        Config {
+            focus_command: None,
+            session_command: None,
+            notify: Default::default(),
+            hooks: Default::default(),
+            escalate: Default::default(),
+            sort: Default::default(),
+        }
+    }
+
+    /// Drive the event loop headlessly, returning the item chosen for
+    /// focus and the final screen text
+    fn run_events(
+        app: &mut App,
+        events: Vec<Event>,
+        width: u16,
+        height: u16,
+    ) -> (Option<InboxItem>, String) {
+        let mut terminal =
+            Terminal::new(ratatui::backend::TestBackend::new(width, height)).unwrap();
+        let focused = run_app(&mut terminal, app, &test_config(), &mut events.into_iter()).unwrap();
+        let buffer = terminal.backend().buffer();
+        let screen = (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        (focused, screen)
+    }
+
+    /// Key presses, one char per key ('\n' is Enter, '\x1b' is Esc)
+    fn keys(keys: &str) -> Vec<Event> {
+        keys.chars()
+            .map(|c| {
+                let code = match c {
+                    '\n' => KeyCode::Enter,
+                    '\x1b' => KeyCode::Esc,
+                    '\t' => KeyCode::Tab,
+                    c => KeyCode::Char(c),
+                };
+                Event::Key(crossterm::event::KeyEvent::new(code, KeyModifiers::NONE))
+            })
+            .collect()
+    }
+
+    fn mouse(kind: MouseEventKind, row: u16) -> Event {
+        Event::Mouse(crossterm::event::MouseEvent {
+            kind,
+            column: 4,
+            row,
+            modifiers: KeyModifiers::NONE,
+        })
+    }
+
+    fn click(row: u16) -> Event {
+        mouse(MouseEventKind::Down(MouseButton::Left), row)
+    }
+
+    fn run_keys(
+        app: &mut App,
+        input: &str,
+        width: u16,
+        height: u16,
+    ) -> (Option<InboxItem>, String) {
+        run_events(app, keys(input), width, height)
+    }
+
+    fn draw_to_string(app: &mut App, width: u16, height: u16) -> String {
+        run_keys(app, "", width, height).1
+    }
+
+    /// App on an in-memory inbox (with a path that is never written)
+    fn app_with(inbox: Inbox, group_by: &[&str]) -> App {
+        let group_by = group_by.iter().map(|g| g.to_string()).collect();
+        App::new(inbox, PathBuf::from("/nonexistent/tael.md"), group_by)
+    }
+
+    fn mixed_inbox() -> Inbox {
+        let mut inbox = sample_inbox();
+        inbox.upsert(make_item(
+            "indexer: Running",
+            5,
+            "crucible",
+            None,
+            Status::Working,
+        ));
+        inbox
+    }
+
+    #[test]
+    fn snapshot_empty_inbox() {
+        let mut app = app_with(Inbox::new(), &[]);
+        insta::assert_snapshot!(draw_to_string(&mut app, 50, 5));
+    }
+
+    #[test]
+    fn snapshot_flat_list() {
+        let mut app = app_with(mixed_inbox(), &[]);
+        insta::assert_snapshot!(draw_to_string(&mut app, 50, 6));
+    }
+
+    #[test]
+    fn snapshot_grouped_by_status_and_proj() {
+        let mut app = app_with(mixed_inbox(), &["status", "proj"]);
+        insta::assert_snapshot!(draw_to_string(&mut app, 50, 10));
+    }
+
+    #[test]
+    fn snapshot_long_messages() {
+        let mut inbox = Inbox::new();
+        inbox.upsert(make_item(
+            "claude: The migration touches every table in the schema and needs a decision on the rollout order",
+            1,
+            "crucible",
+            None,
+            Status::Waiting,
+        ));
+        let mut app = app_with(inbox, &["proj"]);
+        insta::assert_snapshot!(draw_to_string(&mut app, 50, 5));
+    }
+
+    #[test]
+    fn snapshot_selection_movement() {
+        let mut app = app_with(mixed_inbox(), &["status"]);
+        // Down past the end clamps, then back up over the last item
+        let (_, screen) = run_keys(&mut app, "jjjjjk", 50, 8);
+        assert_eq!(app.selected_group.as_deref(), Some("Background"));
+        insta::assert_snapshot!(screen);
+    }
+
+    #[test]
+    fn snapshot_narrow_width() {
+        let mut app = app_with(mixed_inbox(), &["proj"]);
+        insta::assert_snapshot!(draw_to_string(&mut app, 20, 8));
+    }
+
+    #[test]
+    fn snapshot_deletion() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("inbox.md");
+        crate::file::save(&path, &mixed_inbox()).unwrap();
+
+        let mut app = App::open(&path, &[], false).unwrap();
+        let (_, screen) = run_keys(&mut app, "jdy", 50, 6);
+        assert_eq!(crate::file::load(&path).unwrap().items.len(), 2);
+        insta::assert_snapshot!(screen);
+    }
+
+    #[test]
+    fn deleting_waiting_item_asks_first() {
+        let (_dir, path, mut app) = file_app(mixed_inbox(), &[]);
+        let (_, screen) = run_keys(&mut app, "d", 50, 7);
+        assert!(screen.contains("Delete waiting item in pane 42? (y/n)"));
+        run_keys(&mut app, "n", 50, 7);
+        assert_eq!(panes_on_disk(&path), vec![42, 17, 5]);
+
+        // Working items go without asking
+        run_keys(&mut app, "jjd", 50, 7);
+        assert_eq!(panes_on_disk(&path), vec![42, 17]);
+    }
+
+    #[test]
+    fn enter_returns_item_to_focus() {
+        let mut app = app_with(mixed_inbox(), &[]);
+        let (focused, _) = run_keys(&mut app, "j\nq", 50, 6);
+        assert_eq!(focused.unwrap().pane_id(), Some(17));
+
+        let (focused, _) = run_keys(&mut app, "q", 50, 6);
+        assert!(focused.is_none());
+    }
+
+    #[test]
+    fn app_uses_custom_inbox_path() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("custom.md");
+        crate::file::save(&path, &sample_inbox()).unwrap();
+
+        let mut app = App::open(&path, &[], false).unwrap();
+        let screen = draw_to_string(&mut app, 40, 6);
+        assert!(screen.contains("▶ [•] claude: Auth question"));
+        assert!(screen.contains("[•] claude: Review PR"));
+
+        // Deleting writes back to the same file
+        let removed = app.delete_selected().unwrap();
+        assert_eq!(removed.pane_id(), Some(42));
+        let on_disk = crate::file::load(&path).unwrap();
+        assert_eq!(on_disk.items.len(), 1);
+        assert_eq!(on_disk.items[0].pane_id(), Some(17));
+
+        // Reload picks up external changes to the custom file
+        crate::file::save(&path, &Inbox::new()).unwrap();
+        app.reload();
+        assert!(draw_to_string(&mut app, 40, 6).contains("(no items)"));
+    }
+
+    #[test]
+    fn app_all_sessions_next_to_custom_path() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let inbox = sample_inbox();
+        let one = |i: usize| Inbox {
+            items: vec![inbox.items[i].clone()],
+        };
+        crate::file::save(&dir.path().join("alpha.md"), &one(0)).unwrap();
+        crate::file::save(&dir.path().join("beta.md"), &one(1)).unwrap();
+
+        let mut app = App::open(&dir.path().join("alpha.md"), &[], true).unwrap();
+        let screen = draw_to_string(&mut app, 40, 8);
+        assert!(screen.contains("alpha"));
+        assert!(screen.contains("beta"));
+
+        // Items are removed from their own session's file
+        // Past the "beta" header onto its item
+        app.next();
+        app.next();
+        app.delete_selected().unwrap();
+        assert!(!dir.path().join("beta.md").exists());
+        assert!(dir.path().join("alpha.md").exists());
+    }
+
+    #[test]
+    fn mouse_click_selects_and_double_click_focuses() {
+        // Rows: 2 crucible, 3 Auth, 4 indexer, 5 tael (master), 6 Review
+        let mut app = app_with(mixed_inbox(), &["proj"]);
+        let (focused, _) = run_events(&mut app, vec![click(6)], 50, 10);
+        assert!(focused.is_none());
+        assert_eq!(app.selected().unwrap().pane_id(), Some(17));
+
+        let (focused, _) = run_events(&mut app, vec![click(4), click(4)], 50, 10);
+        assert_eq!(focused.unwrap().pane_id(), Some(5));
+
+        // Clicks outside the list do nothing
+        run_events(&mut app, vec![click(0), click(9)], 50, 10);
+        assert_eq!(app.selected().unwrap().pane_id(), Some(5));
+    }
+
+    #[test]
+    fn mouse_scroll_moves_selection() {
+        let mut app = app_with(mixed_inbox(), &[]);
+        let down = mouse(MouseEventKind::ScrollDown, 3);
+        let up = mouse(MouseEventKind::ScrollUp, 3);
+        run_events(&mut app, vec![down.clone(), down.clone(), down], 50, 6);
+        assert_eq!(app.selected_item, Some(2));
+        run_events(&mut app, vec![up], 50, 6);
+        assert_eq!(app.selected_item, Some(1));
+    }
+
+    #[test]
+    fn snapshot_click_header_collapses_group() {
+        let mut app = app_with(mixed_inbox(), &["proj"]);
+        let (_, screen) = run_events(&mut app, vec![click(2)], 50, 8);
+        // The clicked header is selected
+        assert_eq!(app.selected_group.as_deref(), Some("crucible"));
+        insta::assert_snapshot!(screen);
+
+        // Clicking again expands it
+        let (_, screen) = run_events(&mut app, vec![click(2)], 50, 8);
+        assert!(screen.contains("Auth question"));
+    }
+
+    #[test]
+    fn fold_groups_from_keyboard() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("inbox.md");
+        crate::file::save(&path, &mixed_inbox()).unwrap();
+        let group_by = ["status".to_string()];
+        let mut app = App::open(&path, &group_by, false).unwrap();
+
+        // `za` on an item folds its group and moves onto the header
+        run_keys(&mut app, "za", 50, 8);
+        assert_eq!(app.selected_group.as_deref(), Some("Waiting for Input"));
+        assert!(app.collapsed.contains("Waiting for Input"));
+
+        // Folds survive reloading the inbox
+        app.reload();
+        let screen = draw_to_string(&mut app, 50, 8);
+        assert!(screen.contains("▸ Waiting for Input (2)"));
+        assert!(!screen.contains("Auth question"));
+
+        // Space on a header expands it again
+        let (_, screen) = run_keys(&mut app, " ", 50, 8);
+        assert!(screen.contains("Auth question"));
+    }
+
+    #[test]
+    fn snapshot_delete_group_asks_first() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("inbox.md");
+        crate::file::save(&path, &mixed_inbox()).unwrap();
+        let group_by = ["status".to_string()];
+        let mut app = App::open(&path, &group_by, false).unwrap();
+
+        // Cursor up onto the "Waiting for Input" header, then d
+        let (_, screen) = run_keys(&mut app, "kd", 50, 8);
+        insta::assert_snapshot!(screen);
+
+        // Anything but y cancels
+        run_keys(&mut app, "n", 50, 8);
+        assert_eq!(app.inbox.items.len(), 3);
+
+        let (_, screen) = run_keys(&mut app, "dy", 50, 8);
+        assert!(screen.contains("Deleted 2 item(s)"));
+        let on_disk = crate::file::load(&path).unwrap();
+        assert_eq!(on_disk.items.len(), 1);
+        assert_eq!(on_disk.items[0].pane_id(), Some(5));
+    }
+
+    fn file_app(inbox: Inbox, group_by: &[&str]) -> (tempfile::TempDir, PathBuf, App) {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("inbox.md");
+        crate::file::save(&path, &inbox).unwrap();
+        let group_by: Vec<String> = group_by.iter().map(|g| g.to_string()).collect();
+        let app = App::open(&path, &group_by, false).unwrap();
+        (dir, path, app)
+    }
+
+    fn panes_on_disk(path: &Path) -> Vec<u32> {
+        let inbox = crate::file::load(path).unwrap();
+        inbox.items.iter().filter_map(|i| i.pane_id()).collect()
+    }
+
+    #[test]
+    fn snapshot_help_overlay() {
+        let mut app = app_with(mixed_inbox(), &[]);
+        let (_, screen) = run_keys(&mut app, "?", 50, 22);
+        insta::assert_snapshot!(screen);
+
+        // Any key closes it without acting
+        run_keys(&mut app, "j", 50, 22);
+        assert!(!app.help);
+        assert_eq!(app.selected_item, Some(0));
+    }
+
+    #[test]
+    fn snapshot_marked_items() {
+        let mut app = app_with(mixed_inbox(), &[]);
+        let (_, screen) = run_keys(&mut app, " jj ", 50, 7);
+        insta::assert_snapshot!(screen);
+
+        // Esc clears marks before it quits
+        run_keys(&mut app, "\x1b", 50, 7);
+        assert!(app.marks.is_empty());
+    }
+
+    #[test]
+    fn bulk_delete_marked_and_undo() {
+        let (_dir, path, mut app) = file_app(mixed_inbox(), &[]);
+        let (_, screen) = run_keys(&mut app, " jj d", 50, 7);
+        assert!(screen.contains("Delete 2 marked item(s)? (y/n)"));
+
+        run_keys(&mut app, "y", 50, 7);
+        assert_eq!(panes_on_disk(&path), vec![17]);
+        assert_eq!(app.inbox.items.len(), 1);
+        assert!(app.marks.is_empty());
+
+        let (_, screen) = run_keys(&mut app, "u", 50, 7);
+        assert!(screen.contains("Undid delete of 2 item(s)"));
+        // Restored items are added back after the others
+        assert_eq!(panes_on_disk(&path), vec![17, 42, 5]);
+
+        let (_, screen) = run_keys(&mut app, "u", 50, 7);
+        assert!(screen.contains("Nothing to undo"));
+    }
+
+    #[test]
+    fn status_change_keeps_updates_made_since_display() {
+        let (_dir, path, mut app) = file_app(mixed_inbox(), &[]);
+        // An agent updates the first item and removes another behind our back
+        let mut store = Store::open(&path);
+        store
+            .add(make_item(
+                "claude: New question",
+                42,
+                "crucible",
+                None,
+                Status::Waiting,
+            ))
+            .unwrap();
+        store.remove(17).unwrap();
+
+        run_keys(&mut app, " j s", 50, 7);
+        let inbox = crate::file::load(&path).unwrap();
+        assert_eq!(inbox.items.len(), 2);
+        let item = inbox
+            .items
+            .iter()
+            .find(|i| i.pane_id() == Some(42))
+            .unwrap();
+        assert_eq!(item.msg(), "claude: New question");
+        assert_eq!(item.status, Status::Working);
+    }
+
+    #[test]
+    fn snapshot_visual_range_status_change() {
+        let (_dir, path, mut app) = file_app(mixed_inbox(), &["status"]);
+        // From the first item down over the Background header to the last item
+        let (_, screen) = run_keys(&mut app, "vjjj", 50, 8);
+        insta::assert_snapshot!(screen);
+
+        run_keys(&mut app, "s", 50, 8);
+        let inbox = crate::file::load(&path).unwrap();
+        let status = |pane| {
+            inbox
+                .items
+                .iter()
+                .find(|i| i.pane_id() == Some(pane))
+                .unwrap()
+                .status
+        };
+        assert_eq!(status(42), Status::Working);
+        assert_eq!(status(17), Status::Working);
+        assert_eq!(status(5), Status::Waiting);
+
+        run_keys(&mut app, "u", 50, 8);
+        let inbox = crate::file::load(&path).unwrap();
+        assert_eq!(inbox.count(Status::Waiting), 2);
+    }
+
+    #[test]
+    fn tab_cycles_through_marked_items() {
+        let mut app = app_with(mixed_inbox(), &[]);
+        run_keys(&mut app, " jj ", 50, 7);
+
+        let tab = || {
+            vec![Event::Key(crossterm::event::KeyEvent::new(
+                KeyCode::Tab,
+                KeyModifiers::NONE,
+            ))]
+        };
+        let mut visited = Vec::new();
+        for _ in 0..3 {
+            let (focused, _) = run_events(&mut app, tab(), 50, 7);
+            // Focusing a marked pane doesn't leave the TUI
+            assert!(focused.is_none());
+            visited.push(app.selected_item.unwrap());
+        }
+        assert_eq!(visited, vec![0, 2, 0]);
+    }
+
+    #[test]
+    fn undo_and_redo_delete() {
+        let (_dir, path, mut app) = file_app(mixed_inbox(), &[]);
+        run_keys(&mut app, "dy", 50, 7);
+        assert_eq!(panes_on_disk(&path), vec![17, 5]);
+
+        let (_, screen) = run_keys(&mut app, "u", 50, 7);
+        assert!(screen.contains("Undid delete of 1 item(s)"));
+        // Restored items are appended to the file
+        assert_eq!(panes_on_disk(&path), vec![17, 5, 42]);
+
+        let ctrl_r = Event::Key(crossterm::event::KeyEvent::new(
+            KeyCode::Char('r'),
+            KeyModifiers::CONTROL,
+        ));
+        let (_, screen) = run_events(&mut app, vec![ctrl_r.clone()], 50, 7);
+        assert!(screen.contains("Redid delete of 1 item(s)"));
+        assert_eq!(panes_on_disk(&path), vec![17, 5]);
+
+        let (_, screen) = run_events(&mut app, vec![ctrl_r], 50, 7);
+        assert!(screen.contains("Nothing to redo"));
+    }
+
+    #[test]
+    fn inline_edit_saves_parsed_line() {
+        let (_dir, path, mut app) = file_app(mixed_inbox(), &[]);
+        let (_, screen) = run_keys(&mut app, "e", 80, 7);
+        assert!(screen.contains("edit: - [ ] claude: Auth question [pane:: 42] [proj:: crucible]"));
+
+        // Esc throws the edit away
+        run_keys(&mut app, " [note:: x]\x1b", 80, 7);
+        assert!(app.edit.is_none());
+        assert!(app.inbox.items[0].get("note").is_none());
+
+        let (_, screen) = run_keys(&mut app, "e [note:: after lunch]\n", 80, 7);
+        assert!(screen.contains("Saved"));
+        let inbox = crate::file::load(&path).unwrap();
+        assert_eq!(inbox.items[0].get("note"), Some("after lunch"));
+        assert_eq!(inbox.items[0].msg(), "claude: Auth question");
+
+        run_keys(&mut app, "u", 80, 7);
+        assert!(crate::file::load(&path).unwrap().items[0]
+            .get("note")
+            .is_none());
+    }
+
+    /// Scripted events that run "editors" directly instead of suspending a terminal
+    struct WithEditor(std::vec::IntoIter<Event>);
+
+    impl EventSource for WithEditor {
+        fn next_event(&mut self) -> io::Result<Option<Event>> {
+            Ok(self.0.next())
+        }
+
+        fn suspend(&mut self, cmd: &mut Command) -> io::Result<ExitStatus> {
+            cmd.status()
+        }
+    }
+
+    #[test]
+    fn external_editor_edits_markdown_snippet() {
+        let (_dir, path, mut app) = file_app(mixed_inbox(), &[]);
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(80, 7)).unwrap();
+        let mut run = |app: &mut App| {
+            let mut events = WithEditor(keys("E").into_iter());
+            run_app(&mut terminal, app, &test_config(), &mut events).unwrap();
+        };
+
+        run(&mut app);
+        assert!(app.message.as_deref().unwrap().contains("Set $EDITOR"));
+
+        // Change the message and the status checkbox
+        app.editor = Some(r"sed -i -e 's/Auth/Login/' -e 's/- \[ \]/- [\/]/'".to_string());
+        run(&mut app);
+        let inbox = crate::file::load(&path).unwrap();
+        let item = inbox
+            .items
+            .iter()
+            .find(|i| i.pane_id() == Some(42))
+            .unwrap();
+        assert_eq!(item.msg(), "claude: Login question");
+        assert_eq!(item.status, Status::Working);
+        assert!(item.since().is_some());
+
+        app.editor = Some("false".to_string());
+        run(&mut app);
+        assert!(app
+            .message
+            .as_deref()
+            .unwrap()
+            .starts_with("Edit discarded"));
+    }
+
+    #[test]
+    fn s_toggles_selected_item_without_marks() {
+        let (_dir, path, mut app) = file_app(mixed_inbox(), &[]);
+        let (_, screen) = run_keys(&mut app, "s", 50, 7);
+        assert!(screen.contains("Changed status of 1 item(s)"));
+        let inbox = crate::file::load(&path).unwrap();
+        assert_eq!(inbox.count(Status::Waiting), 1);
+    }
+
+    #[test]
+    fn add_form_creates_item() {
+        let (_dir, path, mut app) = file_app(mixed_inbox(), &[]);
+        let (_, screen) = run_keys(&mut app, "a\n", 60, 10);
+        assert!(screen.contains("msg is required"));
+        assert!(app.add.is_some());
+
+        let (_, screen) = run_keys(
+            &mut app,
+            "review PR #42\t \tproj=home 'where=after lunch'",
+            60,
+            10,
+        );
+        insta::assert_snapshot!(screen);
+
+        run_keys(&mut app, "\n", 60, 10);
+        assert!(app.add.is_none());
+        let inbox = crate::file::load(&path).unwrap();
+        let note = inbox
+            .items
+            .iter()
+            .find(|i| i.msg() == "review PR #42")
+            .unwrap();
+        assert_eq!(note.status, Status::Working);
+        assert_eq!(note.pane_id(), None);
+        assert_eq!(note.get("where"), Some("after lunch"));
+        assert_eq!(app.selected().unwrap().msg(), "review PR #42");
+
+        // Pane-less items never replace each other
+        run_keys(&mut app, "areview PR #42\n", 60, 10);
+        assert_eq!(crate::file::load(&path).unwrap().items.len(), 5);
+
+        run_keys(&mut app, "a\x1b", 60, 10);
+        assert!(app.add.is_none());
+    }
+
+    #[test]
+    fn delete_and_edit_pane_less_items_by_id() {
+        let mut inbox = mixed_inbox();
+        let mut note = InboxItem::new(HashMap::new(), Status::Working);
+        note.attrs.insert("msg".to_string(), "lunch".to_string());
+        note.attrs.insert("key".to_string(), "id".to_string());
+        inbox.upsert(note.clone());
+        inbox.upsert(note);
+        let (_dir, path, mut app) = file_app(inbox, &[]);
+        let notes = |path: &Path| {
+            crate::file::load(path)
+                .unwrap()
+                .items
+                .into_iter()
+                .filter(|i| i.pane_id().is_none())
+                .count()
+        };
+        assert_eq!(notes(&path), 2);
+
+        // Both notes sort after the Waiting items
+        run_keys(&mut app, "jjjd", 50, 8);
+        assert_eq!(notes(&path), 1);
+        run_keys(&mut app, "ke [who:: me]\n", 50, 8);
+        let inbox = crate::file::load(&path).unwrap();
+        assert!(inbox.items.iter().any(|i| i.get("who") == Some("me")));
+        assert_eq!(inbox.items.len(), 4);
+    }
+
+    #[test]
+    fn o_cycles_sort_orders_keeping_selection() {
+        let mut inbox = mixed_inbox();
+        for (item, since) in inbox.items.iter_mut().zip([300, 100, 200]) {
+            item.attrs.insert("since".to_string(), since.to_string());
+        }
+        let mut app = app_with(inbox, &[]);
+        app.set_sort("proj".parse().unwrap());
+        let panes = |app: &App| {
+            app.inbox
+                .items
+                .iter()
+                .filter_map(|i| i.pane_id())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(panes(&app), vec![42, 5, 17]);
+
+        // Configured order first, then the presets
+        let (_, screen) = run_keys(&mut app, "o", 50, 7);
+        assert!(screen.contains("Sort: status,priority,proj"));
+        assert_eq!(panes(&app), vec![42, 17, 5]);
+        assert_eq!(app.selected().unwrap().pane_id(), Some(42));
+
+        // Oldest first
+        run_keys(&mut app, "o", 50, 7);
+        assert_eq!(app.sort.to_string(), "-age");
+        assert_eq!(panes(&app), vec![17, 5, 42]);
+        assert_eq!(app.selected().unwrap().pane_id(), Some(42));
+
+        run_keys(&mut app, "ooo", 50, 7);
+        assert_eq!(app.sort.to_string(), "proj");
+    }
+
+    #[test]
+    fn overdue_urgent_items_ring_once_and_flash() {
+        let mut inbox = sample_inbox();
+        inbox.items[1]
+            .attrs
+            .insert("priority".to_string(), "urgent".to_string());
+        inbox.items[1]
+            .attrs
+            .insert("since".to_string(), "1".to_string());
+        let (_dir, _path, mut app) = file_app(inbox, &[]);
+
+        let (_, screen) = run_keys(&mut app, "", 60, 8);
+        assert!(screen.contains("1 urgent item(s) waiting over 10m"));
+        // Already rang for this item
+        assert!(!app.escalate(&test_config(), crate::time::now()));
+
+        let colored = render_list(&app.inbox, 80, true, &[]);
+        assert!(colored.contains("\x1b[1;31m"));
+    }
+
+    #[test]
+    fn snooze_hides_items_in_their_own_section() {
+        let (_dir, path, mut app) = file_app(sample_inbox(), &["status"]);
+
+        // zz snoozes the selected item for 30m; the collapsed section takes the cursor
+        let (_, screen) = run_keys(&mut app, "zz", 60, 8);
+        assert!(screen.contains("Snoozed 1 item(s) for 30m"));
+        assert!(screen.contains("▸ Snoozed (1)"));
+        assert!(!screen.contains("Auth question"));
+        let on_disk = crate::file::load(&path).unwrap();
+        let snoozed = on_disk.items.iter().find(|i| i.pane_id() == Some(42));
+        assert!(snoozed.unwrap().is_snoozed(crate::time::now()));
+
+        // Expand the section, then wake the item
+        let (_, screen) = run_keys(&mut app, "\nj", 60, 8);
+        assert!(screen.contains("Auth question (") && screen.contains("m left)"));
+        let (_, screen) = run_keys(&mut app, "zw", 60, 8);
+        assert!(screen.contains("Woke 1 item(s)"));
+        assert!(!screen.contains("Snoozed ("));
+
+        // A status change ends a snooze too
+        run_keys(&mut app, "z1", 60, 8);
+        assert_eq!(app.snoozed_from, 1);
+        let now = crate::time::now();
+        let on_disk = crate::file::load(&path).unwrap();
+        let mut item = on_disk
+            .items
+            .into_iter()
+            .find(|i| i.is_snoozed(now))
+            .unwrap();
+        item.status = Status::Working;
+        Store::open(&path).add(item).unwrap();
+        app.reload();
+        assert_eq!(app.snoozed_from, 2);
+    }
+
+    #[test]
+    fn focusing_or_viewing_details_marks_items_seen() {
+        let (_dir, path, mut app) = file_app(sample_inbox(), &[]);
+        let seen_on_disk = || {
+            let mut panes: Vec<u32> = crate::file::load(&path)
+                .unwrap()
+                .items
+                .iter()
+                .filter(|i| i.is_seen())
+                .filter_map(|i| i.pane_id())
+                .collect();
+            panes.sort();
+            panes
+        };
+
+        let (focused, _) = run_keys(&mut app, "\n", 60, 20);
+        assert_eq!(focused.unwrap().pane_id(), Some(42));
+        assert_eq!(seen_on_disk(), vec![42]);
+        // Seeing an item isn't an update in the history
+        let history = crate::history::path_for(&path);
+        let events = crate::history::load(&history).unwrap_or_default();
+        assert!(events
+            .iter()
+            .all(|e| e.event != crate::history::EventKind::Update));
+
+        // The details panel marks whatever it shows
+        let (_, screen) = run_keys(&mut app, "ji", 60, 20);
+        assert!(screen.contains("pane    17"));
+        assert!(screen.contains("[ ] claude: Auth question"));
+        assert!(!screen.contains("unseen"));
+        assert_eq!(seen_on_disk(), vec![17, 42]);
+
+        // A new message from the agent makes it unseen again
+        run_keys(&mut app, "i", 60, 20);
+        let update = make_item("claude: Review again", 17, "tael", None, Status::Waiting);
+        Store::open(&path).add(update).unwrap();
+        app.reload();
+        let screen = draw_to_string(&mut app, 60, 20);
+        assert!(screen.contains("[•] claude: Review again"));
+        assert!(screen.contains("• 1 unseen"));
+
+        // Updated again before the display caught up: neither the new message
+        // nor the agent's other changes are touched
+        let mut newer = make_item("claude: Third try", 17, "tael", None, Status::Working);
+        newer.attrs.insert("branch".to_string(), "fix".to_string());
+        Store::open(&path).add(newer).unwrap();
+        run_keys(&mut app, "i", 60, 20);
+        let stored = crate::file::load(&path).unwrap();
+        let stored = stored
+            .items
+            .iter()
+            .find(|i| i.pane_id() == Some(17))
+            .unwrap();
+        assert_eq!(stored.msg(), "claude: Third try");
+        assert_eq!(stored.status, Status::Working);
+        assert!(!stored.is_seen());
+    }
 }
@@ -1,7 +1,14 @@
 //! Core types for agent inbox
 
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::hash::{BuildHasher, RandomState};
+use std::sync::LazyLock;
+
+// Matches {key} template placeholders
+static PLACEHOLDER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{([A-Za-z_][A-Za-z0-9_]*)\}").expect("valid regex"));
 
 /// Status of an inbox item
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -31,6 +38,30 @@ impl Status {
         }
     }
 
+    /// Lowercase name (matches the JSON representation)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Waiting => "waiting",
+            Self::Working => "working",
+        }
+    }
+
+    /// Icon for compact status bar display
+    pub fn to_icon(self) -> &'static str {
+        match self {
+            Self::Waiting => "⏳",
+            Self::Working => "⚙",
+        }
+    }
+
+    /// The other status
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Waiting => Self::Working,
+            Self::Working => Self::Waiting,
+        }
+    }
+
     /// Section name for TUI display
     pub fn section_name(self) -> &'static str {
         match self {
@@ -40,8 +71,54 @@ impl Status {
     }
 }
 
+/// How urgently an item needs attention (convention: "priority" attr).
+/// Ordered most urgent first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Priority {
+    Urgent,
+    High,
+    #[default]
+    Normal,
+    Low,
+}
+
+impl Priority {
+    /// Lowercase name, as stored in the attr
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Urgent => "urgent",
+            Self::High => "high",
+            Self::Normal => "normal",
+            Self::Low => "low",
+        }
+    }
+}
+
+impl std::str::FromStr for Priority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "urgent" => Ok(Self::Urgent),
+            "high" => Ok(Self::High),
+            "normal" => Ok(Self::Normal),
+            "low" => Ok(Self::Low),
+            other => Err(format!(
+                "invalid priority '{}': use low, normal, high or urgent",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// A single inbox item with generic attributes
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InboxItem {
     /// Generic key-value attributes
     pub attrs: HashMap<String, String>,
@@ -60,6 +137,11 @@ impl InboxItem {
         self.attrs.get(key).map(|s| s.as_str())
     }
 
+    /// Get the stable short ID (convention: "id" attr, assigned on upsert)
+    pub fn id(&self) -> Option<&str> {
+        self.get("id")
+    }
+
     /// Get pane ID (convention: "pane" attr parsed as u32)
     pub fn pane_id(&self) -> Option<u32> {
         self.get("pane").and_then(|s| s.parse().ok())
@@ -79,6 +161,68 @@ impl InboxItem {
     pub fn branch(&self) -> Option<&str> {
         self.get("branch")
     }
+
+    /// Fill a template: {status}, {pane_id} and any attr such as {msg} or
+    /// {proj}. Missing attrs expand to an empty string.
+    pub fn fill_template(&self, template: &str) -> String {
+        PLACEHOLDER_RE
+            .replace_all(template, |caps: &regex::Captures| match &caps[1] {
+                "status" => self.status.as_str(),
+                "pane_id" => self.get("pane").unwrap_or(""),
+                key => self.get(key).unwrap_or(""),
+            })
+            .into_owned()
+    }
+
+    /// Get unix time the item entered its current status (convention: "since" attr)
+    pub fn since(&self) -> Option<u64> {
+        self.get("since").and_then(|s| s.parse().ok())
+    }
+
+    /// Get unix time a snooze ends (convention: "snooze_until" attr)
+    pub fn snooze_until(&self) -> Option<u64> {
+        self.get("snooze_until").and_then(|s| s.parse().ok())
+    }
+
+    /// Whether the item is hidden from the default views at `now`
+    pub fn is_snoozed(&self, now: u64) -> bool {
+        self.snooze_until().is_some_and(|until| until > now)
+    }
+
+    /// Get priority (convention: "priority" attr; missing or unknown is normal)
+    pub fn priority(&self) -> Priority {
+        self.get("priority")
+            .and_then(|p| p.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// Whether the item is protected from `clear` (convention: "pinned" or
+    /// "sticky" attr set to true/yes/1/on)
+    pub fn is_pinned(&self) -> bool {
+        self.flag("pinned") || self.flag("sticky")
+    }
+
+    /// Whether the user has looked at the item since its message last
+    /// changed (convention: "seen" attr set to true/yes/1/on)
+    pub fn is_seen(&self) -> bool {
+        self.flag("seen")
+    }
+
+    /// Whether a boolean attr is set to true/yes/1/on
+    fn flag(&self, key: &str) -> bool {
+        self.get(key)
+            .is_some_and(|v| matches!(v.to_ascii_lowercase().as_str(), "true" | "yes" | "1" | "on"))
+    }
+
+    /// Get agent name (convention: "agent" attr, else the "agent: ..." msg prefix)
+    pub fn agent(&self) -> Option<&str> {
+        self.get("agent").or_else(|| {
+            self.msg()
+                .split_once(": ")
+                .map(|(prefix, _)| prefix)
+                .filter(|p| !p.is_empty() && !p.contains(' '))
+        })
+    }
 }
 
 /// The inbox containing all items
@@ -98,34 +242,191 @@ impl Inbox {
         self.items.is_empty()
     }
 
-    /// Add or update an item by pane attr
-    pub fn upsert(&mut self, item: InboxItem) {
-        if let Some(pane) = item.pane_id() {
-            if let Some(existing) = self.items.iter_mut().find(|i| i.pane_id() == Some(pane)) {
-                *existing = item;
-                self.sort();
-                return;
+    /// Count items with the given status
+    pub fn count(&self, status: Status) -> usize {
+        self.items.iter().filter(|i| i.status == status).count()
+    }
+
+    /// Index of the item that `item` would replace on upsert: the one with
+    /// the same id, else the same value of the attr named by its "key" attr
+    /// (a custom dedupe key), else the same pane, else (for items with neither
+    /// a pane nor a key) the pane-less item with the same msg and proj
+    pub fn find(&self, item: &InboxItem) -> Option<usize> {
+        if let Some(id) = item.id() {
+            if let Some(idx) = self.items.iter().position(|i| i.id() == Some(id)) {
+                return Some(idx);
             }
         }
+        if let Some((key, value)) = item
+            .get("key")
+            .and_then(|key| item.get(key).map(|value| (key, value)))
+        {
+            return self.items.iter().position(|i| i.get(key) == Some(value));
+        }
+        match item.pane_id() {
+            Some(pane) => self.items.iter().position(|i| i.pane_id() == Some(pane)),
+            None if item.get("key").is_some() => None,
+            None => self.items.iter().position(|i| {
+                i.pane_id().is_none() && i.msg() == item.msg() && i.get("proj") == item.get("proj")
+            }),
+        }
+    }
+
+    /// Get an item by id
+    pub fn by_id(&self, id: &str) -> Option<&InboxItem> {
+        self.items.iter().find(|i| i.id() == Some(id))
+    }
+
+    /// A random short ID not used by any item
+    pub fn new_id(&self) -> String {
+        let state = RandomState::new();
+        (0u64..)
+            .map(|n| short_id(state.hash_one(n)))
+            .find(|id| self.by_id(id).is_none())
+            .expect("unbounded")
+    }
+
+    /// Give items without an id (e.g. from files written by older versions)
+    /// one derived from their contents, so it stays the same until saved
+    pub fn ensure_ids(&mut self) {
+        for idx in 0..self.items.len() {
+            if self.items[idx].id().is_some() {
+                continue;
+            }
+            let mut attrs: Vec<_> = self.items[idx].attrs.iter().collect();
+            attrs.sort();
+            let mut seed = Vec::new();
+            for (key, value) in attrs {
+                seed.extend(key.bytes().chain([0]).chain(value.bytes()).chain([0]));
+            }
+            seed.extend(self.items[idx].status.as_str().bytes());
+            let id = (0u64..)
+                .map(|salt| {
+                    let mut bytes = seed.clone();
+                    bytes.extend(salt.to_le_bytes());
+                    short_id(fnv1a(&bytes))
+                })
+                .find(|id| self.by_id(id).is_none())
+                .expect("unbounded");
+            self.items[idx].attrs.insert("id".to_string(), id);
+        }
+    }
+
+    /// Add or update an item (matched as in `find`), returning the replaced
+    /// item if any. Updates keep the item's place and id; new items are
+    /// appended and get an id if they have none. An update that keeps the
+    /// status keeps the original "since" timestamp; one that changes it
+    /// ends any snooze. An update that keeps the message stays seen.
+    pub fn upsert(&mut self, mut item: InboxItem) -> Option<InboxItem> {
+        if let Some(idx) = self.find(&item) {
+            let existing = &mut self.items[idx];
+            if existing.status == item.status {
+                if let Some(since) = existing.get("since") {
+                    item.attrs.insert("since".to_string(), since.to_string());
+                }
+            } else {
+                item.attrs.remove("snooze_until");
+            }
+            if existing.msg() == item.msg() {
+                if let Some(seen) = existing.get("seen") {
+                    item.attrs
+                        .entry("seen".to_string())
+                        .or_insert_with(|| seen.to_string());
+                }
+            }
+            if let Some(id) = existing.id() {
+                item.attrs
+                    .entry("id".to_string())
+                    .or_insert_with(|| id.to_string());
+            }
+            return Some(std::mem::replace(existing, item));
+        }
+        if item.id().is_none() {
+            let id = self.new_id();
+            item.attrs.insert("id".to_string(), id);
+        }
         self.items.push(item);
-        self.sort();
+        None
+    }
+
+    /// Store an item exactly as given, replacing the one with the same id.
+    /// Returns the replaced item if any.
+    pub fn put(&mut self, item: InboxItem) -> Option<InboxItem> {
+        let idx = item
+            .id()
+            .and_then(|id| self.items.iter().position(|i| i.id() == Some(id)));
+        match idx {
+            Some(idx) => Some(std::mem::replace(&mut self.items[idx], item)),
+            None => {
+                self.items.push(item);
+                None
+            }
+        }
+    }
+
+    /// Remove all items for a pane ID, returning them
+    pub fn remove(&mut self, pane_id: u32) -> Vec<InboxItem> {
+        let (removed, kept) = std::mem::take(&mut self.items)
+            .into_iter()
+            .partition(|i| i.pane_id() == Some(pane_id));
+        self.items = kept;
+        removed
     }
 
-    /// Remove an item by pane ID
-    pub fn remove(&mut self, pane_id: u32) -> bool {
-        let len_before = self.items.len();
-        self.items.retain(|i| i.pane_id() != Some(pane_id));
-        self.items.len() < len_before
+    /// Remove an item by id, returning it if found
+    pub fn remove_id(&mut self, id: &str) -> Option<InboxItem> {
+        let idx = self.items.iter().position(|i| i.id() == Some(id))?;
+        Some(self.items.remove(idx))
     }
 
-    /// Sort items: Waiting before Working, then by proj
-    fn sort(&mut self) {
-        self.items.sort_by(|a, b| match (a.status, b.status) {
-            (Status::Waiting, Status::Working) => std::cmp::Ordering::Less,
-            (Status::Working, Status::Waiting) => std::cmp::Ordering::Greater,
-            _ => a.proj().cmp(&b.proj()),
-        });
+    /// Remove every item whose id is in `ids`, returning them in inbox order
+    pub fn remove_many(&mut self, ids: &[String]) -> Vec<InboxItem> {
+        let (removed, kept) = std::mem::take(&mut self.items)
+            .into_iter()
+            .partition(|i| i.id().is_some_and(|id| ids.iter().any(|x| x == id)));
+        self.items = kept;
+        removed
     }
+
+    /// Ids of unpinned items waiting or working since before `cutoff` (items
+    /// without a `since` attr are kept)
+    pub fn expired(&self, cutoff: u64) -> Vec<String> {
+        self.items
+            .iter()
+            .filter(|i| !i.is_pinned() && i.since().is_some_and(|since| since < cutoff))
+            .filter_map(|i| i.id().map(str::to_string))
+            .collect()
+    }
+
+    /// Remove every item that is not pinned, returning the removed items
+    pub fn clear(&mut self) -> Vec<InboxItem> {
+        let (kept, removed) = std::mem::take(&mut self.items)
+            .into_iter()
+            .partition(InboxItem::is_pinned);
+        self.items = kept;
+        removed
+    }
+}
+
+/// 64-bit FNV-1a hash, which (unlike `DefaultHasher`) is the same across
+/// Rust releases, so ids given to old files don't change between builds
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Four lowercase letters/digits from a hash
+fn short_id(hash: u64) -> String {
+    const ALPHABET: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut n = hash;
+    (0..4)
+        .map(|_| {
+            let c = ALPHABET[(n % 36) as usize] as char;
+            n /= 36;
+            c
+        })
+        .collect()
 }
 
 /// Test utilities (available to other modules via pub use)
@@ -150,6 +451,17 @@ pub mod test_utils {
         }
         InboxItem { attrs, status }
     }
+
+    /// Wait for a background command to have written `lines` lines to a log
+    pub fn read_log(log: &std::path::Path, lines: usize) -> String {
+        for _ in 0..250 {
+            match std::fs::read_to_string(log) {
+                Ok(s) if s.lines().count() >= lines => return s,
+                _ => std::thread::sleep(std::time::Duration::from_millis(20)),
+            }
+        }
+        std::fs::read_to_string(log).unwrap_or_default()
+    }
 }
 
 #[cfg(test)]
@@ -172,4 +484,269 @@ mod tests {
         assert_eq!(item.get("pane"), Some("42"));
         assert_eq!(item.pane_id(), Some(42));
     }
+
+    #[test]
+    fn priority_from_attr() {
+        let mut item = test_utils::make_item("hi", 1, "tael", None, Status::Waiting);
+        assert_eq!(item.priority(), Priority::Normal);
+        item.attrs
+            .insert("priority".to_string(), "Urgent".to_string());
+        assert_eq!(item.priority(), Priority::Urgent);
+        assert!(Priority::Urgent < Priority::Low);
+        assert!("asap".parse::<Priority>().is_err());
+    }
+
+    #[test]
+    fn fill_template_placeholders() {
+        let item = test_utils::make_item("hi", 42, "tael", None, Status::Working);
+        assert_eq!(
+            item.fill_template("{status} {proj}/{pane_id}: {msg}{branch}"),
+            "working tael/42: hi"
+        );
+    }
+
+    #[test]
+    fn agent_from_attr_or_msg_prefix() {
+        let item = test_utils::make_item("claude-code: Waiting", 1, "tael", None, Status::Waiting);
+        assert_eq!(item.agent(), Some("claude-code"));
+
+        let mut item = test_utils::make_item("Needs review", 1, "tael", None, Status::Waiting);
+        assert_eq!(item.agent(), None);
+        item.attrs.insert("agent".to_string(), "codex".to_string());
+        assert_eq!(item.agent(), Some("codex"));
+    }
+
+    #[test]
+    fn upsert_and_remove_return_items() {
+        let mut inbox = Inbox::new();
+        let first = test_utils::make_item("first", 1, "tael", None, Status::Working);
+        assert!(inbox.upsert(first).is_none());
+
+        let second = test_utils::make_item("second", 1, "tael", None, Status::Waiting);
+        let replaced = inbox.upsert(second).unwrap();
+        assert_eq!(replaced.msg(), "first");
+        assert_eq!(inbox.items.len(), 1);
+
+        assert_eq!(inbox.remove(1)[0].msg(), "second");
+        assert!(inbox.remove(1).is_empty());
+    }
+
+    #[test]
+    fn upsert_keeps_since_while_status_unchanged() {
+        let mut inbox = Inbox::new();
+        let mut first = test_utils::make_item("first", 1, "tael", None, Status::Waiting);
+        first.attrs.insert("since".to_string(), "100".to_string());
+        inbox.upsert(first);
+
+        let mut same = test_utils::make_item("again", 1, "tael", None, Status::Waiting);
+        same.attrs.insert("since".to_string(), "200".to_string());
+        inbox.upsert(same);
+        assert_eq!(inbox.items[0].since(), Some(100));
+
+        let mut changed = test_utils::make_item("busy", 1, "tael", None, Status::Working);
+        changed.attrs.insert("since".to_string(), "300".to_string());
+        inbox.upsert(changed);
+        assert_eq!(inbox.items[0].since(), Some(300));
+    }
+
+    #[test]
+    fn remove_many_by_id() {
+        let mut inbox = Inbox::new();
+        for pane in 1..=4 {
+            let mut item = test_utils::make_item("x", pane, "tael", None, Status::Waiting);
+            item.attrs.insert("id".to_string(), format!("i{}", pane));
+            inbox.upsert(item);
+        }
+        let ids = ["i2", "i4", "i9"].map(String::from);
+        let removed = inbox.remove_many(&ids);
+        let panes = |items: &[InboxItem]| items.iter().map(|i| i.pane_id()).collect::<Vec<_>>();
+        assert_eq!(panes(&removed), vec![Some(2), Some(4)]);
+        assert_eq!(panes(&inbox.items), vec![Some(1), Some(3)]);
+    }
+
+    #[test]
+    fn expired_skips_pinned_and_recent_items() {
+        let mut inbox = Inbox::new();
+        for (pane, since, pinned) in [(1, 100, false), (2, 100, true), (3, 900, false)] {
+            let mut item = test_utils::make_item("msg", pane, "tael", None, Status::Waiting);
+            item.attrs.insert("id".to_string(), format!("i{}", pane));
+            item.attrs.insert("since".to_string(), since.to_string());
+            if pinned {
+                item.attrs.insert("sticky".to_string(), "1".to_string());
+            }
+            inbox.items.push(item);
+        }
+        inbox.items.push(test_utils::make_item(
+            "no since",
+            4,
+            "tael",
+            None,
+            Status::Waiting,
+        ));
+        assert_eq!(inbox.expired(500), vec!["i1".to_string()]);
+    }
+
+    #[test]
+    fn clear_keeps_pinned_items() {
+        let mut inbox = Inbox::new();
+        for pane in 1..=3 {
+            inbox.upsert(test_utils::make_item(
+                "x",
+                pane,
+                "tael",
+                None,
+                Status::Waiting,
+            ));
+        }
+        inbox.items[0]
+            .attrs
+            .insert("pinned".to_string(), "true".to_string());
+        inbox.items[2]
+            .attrs
+            .insert("sticky".to_string(), "yes".to_string());
+        inbox.items[1]
+            .attrs
+            .insert("pinned".to_string(), "false".to_string());
+
+        let removed = inbox.clear();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].pane_id(), Some(2));
+        assert_eq!(inbox.items.len(), 2);
+        assert!(inbox.items.iter().all(InboxItem::is_pinned));
+    }
+
+    #[test]
+    fn status_change_ends_snooze() {
+        let mut inbox = Inbox::new();
+        let mut item = test_utils::make_item("hi", 1, "tael", None, Status::Working);
+        item.attrs
+            .insert("snooze_until".to_string(), "2000".to_string());
+        inbox.upsert(item.clone());
+        assert!(inbox.items[0].is_snoozed(1999));
+        assert!(!inbox.items[0].is_snoozed(2000));
+
+        // Same status: the new snooze stands; a status change drops it
+        inbox.upsert(item.clone());
+        assert_eq!(inbox.items[0].snooze_until(), Some(2000));
+        item.status = Status::Waiting;
+        inbox.upsert(item);
+        assert_eq!(inbox.items[0].snooze_until(), None);
+    }
+
+    #[test]
+    fn new_message_resets_seen() {
+        let mut inbox = Inbox::new();
+        let mut item = test_utils::make_item("Approve?", 1, "tael", None, Status::Waiting);
+        inbox.upsert(item.clone());
+        assert!(!inbox.items[0].is_seen());
+        inbox.items[0]
+            .attrs
+            .insert("seen".to_string(), "true".to_string());
+
+        // The agent repeating itself keeps it seen; a new message doesn't
+        inbox.upsert(item.clone());
+        assert!(inbox.items[0].is_seen());
+        item.attrs
+            .insert("msg".to_string(), "Approve again?".to_string());
+        inbox.upsert(item);
+        assert!(!inbox.items[0].is_seen());
+    }
+
+    #[test]
+    fn upsert_assigns_stable_ids() {
+        let mut inbox = Inbox::new();
+        inbox.upsert(test_utils::make_item("a", 1, "tael", None, Status::Waiting));
+        let id = inbox.items[0].id().unwrap().to_string();
+        assert_eq!(id.len(), 4);
+
+        // Updating by pane keeps the id
+        inbox.upsert(test_utils::make_item("b", 1, "tael", None, Status::Working));
+        assert_eq!(inbox.items[0].id(), Some(id.as_str()));
+        assert_eq!(inbox.by_id(&id).unwrap().msg(), "b");
+
+        // Pane-less items are separate from pane items, and can be updated
+        // by id; ones keyed by their id never replace each other
+        let mut note = InboxItem::new(HashMap::new(), Status::Waiting);
+        note.attrs.insert("msg".to_string(), "note".to_string());
+        note.attrs.insert("key".to_string(), "id".to_string());
+        assert!(inbox.upsert(note.clone()).is_none());
+        assert!(inbox.upsert(note).is_none());
+        assert_eq!(inbox.items.len(), 3);
+        let note = inbox.items.iter().find(|i| i.pane_id().is_none()).unwrap();
+        let note_id = note.id().unwrap().to_string();
+        let mut edited = note.clone();
+        edited.attrs.insert("msg".to_string(), "edited".to_string());
+        assert!(inbox.upsert(edited).is_some());
+        assert_eq!(inbox.by_id(&note_id).unwrap().msg(), "edited");
+
+        assert_eq!(inbox.remove_id(&id).unwrap().msg(), "b");
+        assert!(inbox.remove_id(&id).is_none());
+    }
+
+    #[test]
+    fn upsert_by_custom_key() {
+        let mut inbox = Inbox::new();
+        let item = |session: &str, pane| {
+            let mut item = test_utils::make_item(session, pane, "tael", None, Status::Waiting);
+            item.attrs
+                .insert("session_id".to_string(), session.to_string());
+            item.attrs
+                .insert("key".to_string(), "session_id".to_string());
+            item
+        };
+        inbox.upsert(item("s1", 1));
+        // Same pane, different session: a separate item
+        assert!(inbox.upsert(item("s2", 1)).is_none());
+        // Same session, other pane: replaces
+        assert_eq!(inbox.upsert(item("s1", 2)).unwrap().pane_id(), Some(1));
+        assert_eq!(inbox.items.len(), 2);
+    }
+
+    #[test]
+    fn ensure_ids_is_deterministic() {
+        let legacy = || {
+            let mut inbox = Inbox::new();
+            for _ in 0..2 {
+                inbox.items.push(test_utils::make_item(
+                    "same",
+                    1,
+                    "tael",
+                    None,
+                    Status::Waiting,
+                ));
+            }
+            inbox.ensure_ids();
+            inbox
+        };
+        let (a, b) = (legacy(), legacy());
+        assert_eq!(a.items[0].id(), b.items[0].id());
+        assert_eq!(a.items[1].id(), b.items[1].id());
+        assert_ne!(a.items[0].id(), a.items[1].id());
+        // Pinned, so ids of legacy items stay put across Rust releases
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn pane_less_items_dedupe_on_msg_and_proj() {
+        let item = |msg: &str, proj: &str| {
+            let mut attrs = HashMap::new();
+            attrs.insert("msg".to_string(), msg.to_string());
+            attrs.insert("proj".to_string(), proj.to_string());
+            InboxItem::new(attrs, Status::Waiting)
+        };
+        let mut inbox = Inbox::new();
+        assert!(inbox.upsert(item("Build failed", "tael")).is_none());
+        assert!(inbox.upsert(item("Build failed", "tael")).is_some());
+        assert!(inbox.upsert(item("Build failed", "other")).is_none());
+        assert!(inbox.upsert(item("Tests failed", "tael")).is_none());
+        assert_eq!(inbox.items.len(), 3);
+
+        // A key attr whose value is missing falls back to the pane
+        let mut keyed = test_utils::make_item("x", 7, "tael", None, Status::Waiting);
+        inbox.upsert(keyed.clone());
+        keyed
+            .attrs
+            .insert("key".to_string(), "session_id".to_string());
+        assert!(inbox.upsert(keyed).is_some());
+    }
 }
@@ -0,0 +1,373 @@
+//! Undo/redo for destructive actions
+//!
+//! After an action changes the inbox, the versions of each item it touched
+//! before and after it are pushed onto a bounded stack in a `.undo.json` file
+//! next to the inbox file, so both the TUI and `tael undo` can revert them.
+//! Undo only reverts items still as the action left them: anything an agent
+//! changed since is left alone.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::Store;
+use crate::watch::Change;
+use crate::{Inbox, InboxItem};
+
+/// Actions kept per inbox file; older ones are dropped
+pub const LIMIT: usize = 20;
+
+/// One item touched by an action (None: absent)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemChange {
+    pub before: Option<InboxItem>,
+    pub after: Option<InboxItem>,
+}
+
+impl ItemChange {
+    /// An item the action removed
+    pub fn removed(item: InboxItem) -> Self {
+        Self {
+            before: Some(item),
+            after: None,
+        }
+    }
+
+    /// What `Store::add` changed, given the item it replaced: the stored
+    /// version is worked out the way the store's upsert merged it
+    pub fn added(replaced: Option<InboxItem>, item: InboxItem) -> Vec<Self> {
+        let mut merged = Inbox {
+            items: replaced.iter().cloned().collect(),
+        };
+        merged.upsert(item);
+        let after = merged.items.pop();
+        match replaced {
+            // Same item, new version
+            Some(before) if before.id() == after.as_ref().and_then(InboxItem::id) => {
+                vec![Self {
+                    before: Some(before),
+                    after,
+                }]
+            }
+            // Replaced by an item with another id
+            Some(before) => vec![
+                Self::removed(before),
+                Self {
+                    before: None,
+                    after,
+                },
+            ],
+            None => vec![Self {
+                before: None,
+                after,
+            }],
+        }
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.before.as_ref().or(self.after.as_ref())?.id()
+    }
+}
+
+/// What an action changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    /// What the action was, e.g. "clear of 3 item(s)"
+    pub label: String,
+    /// Unix seconds
+    pub ts: u64,
+    pub changes: Vec<ItemChange>,
+}
+
+/// Undo and redo stacks (most recent last)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Stacks {
+    pub undo: Vec<Entry>,
+    pub redo: Vec<Entry>,
+}
+
+/// Result of an undo or redo
+#[derive(Debug)]
+pub struct Step {
+    /// The action's label
+    pub label: String,
+    /// Changes made to the inbox
+    pub changes: Vec<Change>,
+    /// Items left alone because they changed after the action
+    pub skipped: usize,
+}
+
+/// Undo file for an inbox file: `<stem>.undo.json` in the same directory
+pub fn path_for(inbox_path: &Path) -> PathBuf {
+    let stem = inbox_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("inbox");
+    inbox_path.with_file_name(format!("{}.undo.json", stem))
+}
+
+/// Load the stacks (empty if the file is missing or unreadable)
+pub fn load(path: &Path) -> Stacks {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save(path: &Path, stacks: &Stacks) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string(stacks)?)
+}
+
+/// Remember the items an action changed (as returned by the store ops, so
+/// other writers' changes in the meantime aren't included). Best-effort like
+/// `history::record`: failures never block the action.
+pub fn record(inbox_path: &Path, label: &str, changes: Vec<ItemChange>) {
+    if changes.is_empty() {
+        return;
+    }
+    let path = path_for(inbox_path);
+    let mut stacks = load(&path);
+    stacks.undo.push(Entry {
+        label: label.to_string(),
+        ts: crate::time::now(),
+        changes,
+    });
+    let excess = stacks.undo.len().saturating_sub(LIMIT);
+    stacks.undo.drain(..excess);
+    // A new action makes the redo history meaningless
+    stacks.redo.clear();
+    let _ = save(&path, &stacks);
+}
+
+/// Remember the items an action removed
+pub fn record_removed(inbox_path: &Path, label: &str, removed: &[InboxItem]) {
+    let changes = removed.iter().cloned().map(ItemChange::removed).collect();
+    record(inbox_path, label, changes);
+}
+
+/// Suffix for an undo/redo message mentioning items that were left alone
+pub fn skipped_note(skipped: usize) -> String {
+    match skipped {
+        0 => String::new(),
+        n => format!(" ({} item(s) changed since, left as they are)", n),
+    }
+}
+
+/// Revert the last action. Returns None if there is nothing to undo.
+pub fn undo(inbox_path: &Path) -> io::Result<Option<Step>> {
+    step(inbox_path, true)
+}
+
+/// Re-apply the last undone action
+pub fn redo(inbox_path: &Path) -> io::Result<Option<Step>> {
+    step(inbox_path, false)
+}
+
+fn step(inbox_path: &Path, undo: bool) -> io::Result<Option<Step>> {
+    let path = path_for(inbox_path);
+    let mut stacks = load(&path);
+    let (from, to) = if undo {
+        (&mut stacks.undo, &mut stacks.redo)
+    } else {
+        (&mut stacks.redo, &mut stacks.undo)
+    };
+    let Some(entry) = from.pop() else {
+        return Ok(None);
+    };
+
+    let mut store = Store::open(inbox_path);
+    let current = store.load()?;
+    let mut puts = Vec::new();
+    let mut removes = Vec::new();
+    let mut applied = Vec::new();
+    let mut changes = Vec::new();
+    let mut skipped = 0;
+    for change in entry.changes {
+        let Some(id) = change.id().map(str::to_string) else {
+            continue;
+        };
+        let (expected, target) = if undo {
+            (&change.after, &change.before)
+        } else {
+            (&change.before, &change.after)
+        };
+        // Don't bring an item back over a newer one for the same pane/key
+        let replaced =
+            expected.is_none() && target.as_ref().is_some_and(|t| current.find(t).is_some());
+        if current.by_id(&id) != expected.as_ref() || replaced {
+            skipped += 1;
+            continue;
+        }
+        changes.push(match (expected, target) {
+            (None, Some(item)) => Change::Added(item.clone()),
+            (Some(_), Some(item)) => Change::Updated(item.clone()),
+            (Some(item), None) => Change::Removed(item.clone()),
+            (None, None) => continue,
+        });
+        match target {
+            Some(item) => puts.push(item.clone()),
+            None => removes.push(id),
+        }
+        applied.push(change);
+    }
+
+    if !puts.is_empty() {
+        store.put_many(puts)?;
+    }
+    if !removes.is_empty() {
+        store.remove_many(&removes)?;
+    }
+    // Only what was applied can be stepped back over
+    to.push(Entry {
+        label: entry.label.clone(),
+        ts: crate::time::now(),
+        changes: applied,
+    });
+    save(&path, &stacks)?;
+
+    Ok(Some(Step {
+        label: entry.label,
+        changes,
+        skipped,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{file, test_utils::make_item, Status};
+    use tempfile::TempDir;
+
+    fn inbox_with(panes: &[u32]) -> Inbox {
+        let mut inbox = Inbox::new();
+        for &pane in panes {
+            inbox.upsert(make_item("x", pane, "tael", None, Status::Waiting));
+        }
+        inbox
+    }
+
+    fn panes(path: &Path) -> Vec<u32> {
+        let inbox = file::load(path).unwrap();
+        inbox.items.iter().filter_map(|i| i.pane_id()).collect()
+    }
+
+    #[test]
+    fn undo_and_redo_revert_changes() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("inbox.md");
+        let before = inbox_with(&[1, 2]);
+        file::save(&path, &before).unwrap();
+
+        // Simulate `tael clear`
+        file::save(&path, &Inbox::new()).unwrap();
+        let removed = before.items.into_iter().map(ItemChange::removed);
+        record(&path, "clear of 2 item(s)", removed.collect());
+
+        let step = undo(&path).unwrap().unwrap();
+        assert_eq!(step.label, "clear of 2 item(s)");
+        assert_eq!((step.changes.len(), step.skipped), (2, 0));
+        assert_eq!(panes(&path), vec![1, 2]);
+        assert!(undo(&path).unwrap().is_none());
+
+        redo(&path).unwrap().unwrap();
+        assert!(panes(&path).is_empty());
+        assert!(redo(&path).unwrap().is_none());
+
+        // Undo works again after a redo
+        undo(&path).unwrap().unwrap();
+        assert_eq!(panes(&path), vec![1, 2]);
+    }
+
+    #[test]
+    fn undo_leaves_later_changes_alone() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("inbox.md");
+        let before = inbox_with(&[1, 2]);
+        file::save(&path, &before).unwrap();
+
+        // Snooze both, then an agent updates one and adds another item
+        let mut after = before.clone();
+        for item in &mut after.items {
+            item.attrs
+                .insert("snooze_until".to_string(), "2000000000".to_string());
+        }
+        file::save(&path, &after).unwrap();
+        let snoozed = before.items.into_iter().zip(after.items);
+        let changes = snoozed.flat_map(|(b, a)| ItemChange::added(Some(b), a));
+        record(&path, "snooze of 2 item(s)", changes.collect());
+        let mut store = Store::open(&path);
+        store
+            .add(make_item("new msg", 2, "tael", None, Status::Working))
+            .unwrap();
+        store
+            .add(make_item("other", 3, "tael", None, Status::Waiting))
+            .unwrap();
+
+        let step = undo(&path).unwrap().unwrap();
+        assert_eq!((step.changes.len(), step.skipped), (1, 1));
+
+        // A removed item doesn't come back over a new one for its pane
+        let removed = store.remove(1).unwrap();
+        let changes = removed.into_iter().map(ItemChange::removed).collect();
+        record(&path, "remove of pane 1", changes);
+        store
+            .add(make_item("again", 1, "tael", None, Status::Waiting))
+            .unwrap();
+        assert_eq!(undo(&path).unwrap().unwrap().skipped, 1);
+        let inbox = file::load(&path).unwrap();
+        let pane = |pane| inbox.items.iter().find(|i| i.pane_id() == Some(pane));
+        assert_eq!(inbox.items.len(), 3);
+        assert!(pane(1).unwrap().get("snooze_until").is_none());
+        assert_eq!(pane(2).unwrap().msg(), "new msg");
+        assert_eq!(pane(3).unwrap().msg(), "other");
+    }
+
+    #[test]
+    fn stack_is_bounded_and_new_actions_drop_redo() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("inbox.md");
+        for pane in 0..(LIMIT as u32 + 5) {
+            let removed = inbox_with(&[pane]).items.into_iter();
+            record(
+                &path,
+                &format!("remove of pane {}", pane),
+                removed.map(ItemChange::removed).collect(),
+            );
+        }
+        let stacks = load(&path_for(&path));
+        assert_eq!(stacks.undo.len(), LIMIT);
+        assert_eq!(stacks.undo[0].label, "remove of pane 5");
+
+        undo(&path).unwrap();
+        assert_eq!(load(&path_for(&path)).redo.len(), 1);
+        let removed = inbox_with(&[1]).items.into_iter();
+        record(&path, "clear", removed.map(ItemChange::removed).collect());
+        assert!(load(&path_for(&path)).redo.is_empty());
+    }
+
+    #[test]
+    fn added_records_the_version_the_store_keeps() {
+        let mut old = make_item("old", 1, "tael", None, Status::Waiting);
+        old.attrs.insert("id".to_string(), "k3f9".to_string());
+        old.attrs.insert("since".to_string(), "100".to_string());
+        let mut new = make_item("new", 1, "tael", None, Status::Waiting);
+        new.attrs.insert("since".to_string(), "200".to_string());
+
+        let changes = ItemChange::added(Some(old.clone()), new.clone());
+        assert_eq!(changes.len(), 1);
+        let after = changes[0].after.as_ref().unwrap();
+        assert_eq!((after.id(), after.since()), (Some("k3f9"), Some(100)));
+
+        // An item with another id takes the old one's place
+        new.attrs.insert("id".to_string(), "zz99".to_string());
+        let changes = ItemChange::added(Some(old), new);
+        assert_eq!(changes.len(), 2);
+        assert!(changes[0].after.is_none());
+        assert!(changes[1].before.is_none());
+    }
+}
@@ -0,0 +1,148 @@
+//! Change events derived by diffing inbox snapshots
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+
+use crate::{file, Inbox, InboxItem};
+
+/// How often the inbox file is checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A single inbox change
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "item", rename_all = "lowercase")]
+pub enum Change {
+    Added(InboxItem),
+    Updated(InboxItem),
+    Removed(InboxItem),
+}
+
+/// Identity of an item across snapshots: its id, else its pane, else its
+/// message
+fn identity(item: &InboxItem) -> String {
+    match (item.id(), item.get("pane")) {
+        (Some(id), _) => format!("id:{}", id),
+        (None, Some(pane)) => format!("pane:{}", pane),
+        (None, None) => format!("msg:{}", item.msg()),
+    }
+}
+
+/// Compute the changes that turn `old` into `new`
+pub fn diff(old: &Inbox, new: &Inbox) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    for item in &new.items {
+        let key = identity(item);
+        match old.items.iter().find(|i| identity(i) == key) {
+            None => changes.push(Change::Added(item.clone())),
+            Some(prev) if prev.status != item.status || !same_attrs(prev, item) => {
+                changes.push(Change::Updated(item.clone()))
+            }
+            Some(_) => {}
+        }
+    }
+
+    for item in &old.items {
+        let key = identity(item);
+        if !new.items.iter().any(|i| identity(i) == key) {
+            changes.push(Change::Removed(item.clone()));
+        }
+    }
+
+    changes
+}
+
+/// Whether two versions differ at most in being seen (which the TUI sets
+/// when showing an item, and isn't news to watchers)
+fn same_attrs(a: &InboxItem, b: &InboxItem) -> bool {
+    let without_seen = |item: &InboxItem| {
+        let mut attrs = item.attrs.clone();
+        attrs.remove("seen");
+        attrs
+    };
+    without_seen(a) == without_seen(b)
+}
+
+/// Poll an inbox file, yielding a fresh snapshot whenever it changes
+/// (starting with the current contents)
+pub fn poll_file(path: &Path) -> impl Iterator<Item = io::Result<Inbox>> {
+    let path: PathBuf = path.to_path_buf();
+    let mut last: Option<Option<SystemTime>> = None;
+
+    std::iter::from_fn(move || loop {
+        let mtime = fs::metadata(&path).and_then(|m| m.modified()).ok();
+        if last != Some(mtime) {
+            last = Some(mtime);
+            return Some(file::load(&path));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::make_item, Status};
+
+    #[test]
+    fn diff_detects_added_updated_removed() {
+        let old = Inbox {
+            items: vec![
+                make_item("same", 1, "tael", None, Status::Waiting),
+                make_item("before", 2, "tael", None, Status::Waiting),
+                make_item("gone", 3, "tael", None, Status::Working),
+            ],
+        };
+        let new = Inbox {
+            items: vec![
+                make_item("same", 1, "tael", None, Status::Waiting),
+                make_item("after", 2, "tael", None, Status::Working),
+                make_item("fresh", 4, "tael", None, Status::Waiting),
+            ],
+        };
+
+        let changes = diff(&old, &new);
+        assert_eq!(changes.len(), 3);
+        assert!(matches!(&changes[0], Change::Updated(i) if i.msg() == "after"));
+        assert!(matches!(&changes[1], Change::Added(i) if i.msg() == "fresh"));
+        assert!(matches!(&changes[2], Change::Removed(i) if i.msg() == "gone"));
+    }
+
+    #[test]
+    fn being_seen_is_not_an_update() {
+        let old = Inbox {
+            items: vec![make_item("hi", 1, "tael", None, Status::Waiting)],
+        };
+        let mut new = old.clone();
+        new.items[0]
+            .attrs
+            .insert("seen".to_string(), "true".to_string());
+        assert!(diff(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn change_serializes_with_event_tag() {
+        let change = Change::Added(make_item("hi", 1, "tael", None, Status::Waiting));
+        let json = serde_json::to_value(&change).unwrap();
+        assert_eq!(json["event"], "added");
+        assert_eq!(json["item"]["attrs"]["msg"], "hi");
+        assert_eq!(json["item"]["status"], "waiting");
+    }
+
+    #[test]
+    fn poll_file_yields_on_change() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("test.md");
+        let mut snapshots = poll_file(&path);
+        assert!(snapshots.next().unwrap().unwrap().is_empty());
+
+        let mut inbox = Inbox::new();
+        inbox.upsert(make_item("hi", 1, "tael", None, Status::Waiting));
+        file::save(&path, &inbox).unwrap();
+        assert_eq!(snapshots.next().unwrap().unwrap().items.len(), 1);
+    }
+}